@@ -0,0 +1,430 @@
+// Parsing of the inert #[builder(...)] attributes, on the struct and on its
+// fields. Problems are reported as syn::Errors spanned at the offending
+// tokens and collected, so that every mistake in the input shows up at once.
+
+use quote::{format_ident, quote, ToTokens};
+
+
+// Collects every attribute error so they can be reported together
+#[derive(Default)]
+pub(crate) struct Errors(Option<syn::Error>);
+
+impl Errors {
+    pub(crate) fn push(&mut self, error: syn::Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(error),
+            None => self.0 = Some(error),
+        }
+    }
+
+    pub(crate) fn to_compile_error(&self) -> proc_macro2::TokenStream {
+        match &self.0 {
+            Some(errors) => errors.to_compile_error(),
+            None => proc_macro2::TokenStream::new(),
+        }
+    }
+}
+
+
+// A string literal naming a generated item, e.g. the "arg" of `each = "arg"`
+fn parse_ident(lit: &syn::LitStr) -> syn::Result<syn::Ident> {
+    lit.parse().map_err(|_| syn::Error::new(lit.span(), format!("`{}` is not a valid identifier", lit.value())))
+}
+
+
+// Options given on the struct itself, e.g. `#[builder(typestate)]`
+#[derive(Default, Clone)]
+pub(crate) struct BuilderOptions {
+    pub(crate) typestate: bool,
+    // Name of the generated builder, `<Struct>Builder` unless overridden
+    pub(crate) name: Option<syn::Ident>,
+    // Visibility of the builder, the struct's own unless overridden
+    pub(crate) vis: Option<syn::Visibility>,
+    // Error type returned by `build`, must implement From<BuilderError>
+    pub(crate) error: Option<syn::Type>,
+    // Take unset fields from the struct's own Default impl
+    pub(crate) default: bool,
+    // Setter options that apply to every field without its own
+    pub(crate) setter: SetterOptions,
+    pub(crate) pattern: Pattern,
+    // Called with the builder before `build` constructs anything
+    pub(crate) validate: Option<syn::Path>,
+    // Generate `from_env`, reading every field from `<prefix><FIELD>`
+    pub(crate) env_prefix: Option<String>,
+    // What list values read by `from_env` are split on, "," unless a field
+    // says otherwise
+    pub(crate) env_separator: Option<String>,
+    // Name of the method that finishes the builder, `build` unless
+    // overridden with `builder(build_fn(name = "..."))`
+    pub(crate) build_fn: Option<syn::Ident>,
+    // Further ways to start a builder, each taking some fields up front
+    pub(crate) constructors: Vec<Constructor>,
+    // Called with the built value, to adjust it before `build` returns it
+    pub(crate) post_build: Option<syn::Path>,
+    // `builder(const)`: const setters and a `const fn build_const`, for
+    // building values in statics and consts
+    pub(crate) const_fn: bool,
+    // Generate `<Struct>Partial`, for layering configuration
+    pub(crate) partial: bool,
+}
+
+// `builder(constructor = "new_with(host: String)")`, an associated fn that
+// starts a builder with the listed fields already set
+#[derive(Clone)]
+pub(crate) struct Constructor {
+    pub(crate) name: syn::Ident,
+    // The fields to set, by their builder name, and the type each one is
+    // taken as, which must be accepted by the field's setter
+    pub(crate) arguments: Vec<(syn::Ident, syn::Type)>,
+}
+
+impl syn::parse::Parse for Constructor {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let arguments = content.parse_terminated(|argument| {
+            let name: syn::Ident = argument.parse()?;
+            argument.parse::<syn::Token![:]>()?;
+            Ok((name, argument.parse()?))
+        }, syn::Token![,])?;
+        Ok(Constructor {
+            name,
+            arguments: arguments.into_iter().collect(),
+        })
+    }
+}
+
+// How setters and `build` take the builder, chosen with
+// `builder(pattern = "...")`
+#[derive(Default, Clone, Copy, PartialEq)]
+pub(crate) enum Pattern {
+    // Setters take and return `&mut self`, `build(&self)` clones each field
+    #[default]
+    Immutable,
+    // Setters take and return `&mut self`, `build(&mut self)` takes the
+    // values out of the builder
+    Mutable,
+    // Setters take and return `self`, `build(self)` moves the values out
+    Owned,
+}
+
+impl BuilderOptions {
+    pub(crate) fn builder_name(&self, struct_name: &syn::Ident) -> syn::Ident {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format_ident!("{}Builder", struct_name),
+        }
+    }
+
+    pub(crate) fn error_type(&self) -> proc_macro2::TokenStream {
+        match &self.error {
+            Some(ty) => ty.to_token_stream(),
+            None => quote!(::derive_builder::BuilderError),
+        }
+    }
+
+    pub(crate) fn build_fn_name(&self) -> syn::Ident {
+        match &self.build_fn {
+            Some(name) => name.clone(),
+            None => format_ident!("build"),
+        }
+    }
+}
+
+pub(crate) fn parse_struct_attributes(attrs: &[syn::Attribute], errors: &mut Errors) -> BuilderOptions {
+    let mut options = BuilderOptions::default();
+    let mut pattern_span = None;
+    let mut env_prefix_span = None;
+    let mut const_span = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("typestate") {
+                options.typestate = true;
+            } else if meta.path.is_ident("name") {
+                options.name = Some(parse_ident(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("vis") {
+                options.vis = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.path.is_ident("error") {
+                options.error = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                options.default = true;
+            } else if meta.path.is_ident("setter") {
+                parse_setter_options(&meta, &mut options.setter)?;
+            } else if meta.path.is_ident("pattern") {
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                options.pattern = match lit.value().as_str() {
+                    "immutable" => Pattern::Immutable,
+                    "mutable" => Pattern::Mutable,
+                    "owned" => Pattern::Owned,
+                    _ => return Err(syn::Error::new(lit.span(), "expected \"immutable\", \"mutable\" or \"owned\"")),
+                };
+                pattern_span = Some(lit.span());
+            } else if meta.path.is_ident("validate") {
+                options.validate = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.path.is_ident("env_prefix") {
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                options.env_prefix = Some(lit.value());
+                env_prefix_span = Some(lit.span());
+            } else if meta.path.is_ident("env_separator") {
+                options.env_separator = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("build_fn") {
+                parse_build_fn_options(&meta, &mut options)?;
+            } else if meta.path.is_ident("constructor") {
+                options.constructors.push(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.path.is_ident("post_build") {
+                options.post_build = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.path.is_ident("partial") {
+                options.partial = true;
+            } else if meta.path.is_ident("const") {
+                options.const_fn = true;
+                const_span = Some(syn::spanned::Spanned::span(&meta.path));
+            } else {
+                return Err(meta.error(format!("unrecognized builder option `{}`", meta.path.to_token_stream())));
+            }
+            Ok(())
+        });
+
+        if let Err(error) = result {
+            errors.push(error);
+        }
+    }
+
+    // A typestate builder changes type with every required setter, so it can
+    // only ever be passed by value
+    if let Some(span) = pattern_span {
+        if options.typestate && options.pattern != Pattern::Owned {
+            errors.push(syn::Error::new(span, "typestate builders always use the owned pattern"));
+        }
+    }
+    if let Some(span) = env_prefix_span {
+        if options.typestate {
+            errors.push(syn::Error::new(span, "typestate builders cannot be read from the environment"));
+            options.env_prefix = None;
+        }
+    }
+
+    // Const setters take the builder by value, and `build_const` can only
+    // do what a const context allows, which rules out calling the struct's
+    // Default or the hooks
+    if let Some(span) = const_span {
+        let conflict = if options.typestate {
+            Some("typestate")
+        } else if pattern_span.is_some() && options.pattern != Pattern::Owned {
+            Some("pattern")
+        } else if options.default {
+            Some("default")
+        } else if options.validate.is_some() {
+            Some("validate")
+        } else if options.post_build.is_some() {
+            Some("post_build")
+        } else {
+            None
+        };
+        if let Some(conflict) = conflict {
+            errors.push(syn::Error::new(span, format!("`builder(const)` cannot be combined with `{}`", conflict)));
+            options.const_fn = false;
+        } else {
+            options.pattern = Pattern::Owned;
+        }
+    }
+
+    options
+}
+
+
+// Options given on a field, e.g. `#[builder(each = "arg")]`
+#[derive(Default)]
+pub(crate) struct FieldOptions {
+    // Name of the field in the builder, and of its setter
+    pub(crate) name: Option<syn::Ident>,
+    // Visibility of the field's setters, the builder's unless overridden.
+    // `builder(private)` is the same as `vis = ""`.
+    pub(crate) vis: Option<syn::Visibility>,
+    // Name of the one-at-a-time setter
+    pub(crate) each: Option<syn::Ident>,
+    // Value used when the field is never set, `Default::default()` for a
+    // bare `builder(default)`
+    pub(crate) default: Option<syn::Expr>,
+    // Set by a bare `builder(default)`, whose fallback is not const
+    pub(crate) bare_default: bool,
+    pub(crate) setter: SetterOptions,
+    // Called with the field's value, if it was set, before `build`
+    // constructs anything
+    pub(crate) validate: Option<syn::Path>,
+    // Builder of the field's own type, kept in the outer builder in place of
+    // a value. A bare `builder(sub_builder)` is parsed as `_`, to be inferred
+    // from the field's type.
+    pub(crate) sub_builder: Option<syn::Type>,
+    // How `<Struct>Partial::merge` combines two sets of `each` elements
+    pub(crate) merge: Option<MergePolicy>,
+    // The environment variable `from_env` reads the field from, in place of
+    // the struct's `env_prefix` and the field's name
+    pub(crate) env: Option<syn::LitStr>,
+    pub(crate) env_separator: Option<String>,
+    // Leave the field out of the builder and give it its default
+    pub(crate) skip: bool,
+    // Leave the field out of the builder and set it by calling this with
+    // the rest of the built value. Until then the field holds its `default`,
+    // or its type's Default, which the type then has to implement.
+    pub(crate) compute: Option<syn::Path>,
+    // Treat the field like an Option, falling back to its Default, for
+    // aliases of Option and Vec that the macro cannot see through
+    pub(crate) optional: bool,
+    // Insist on the field being set even if it is an Option or a Vec
+    pub(crate) required: bool,
+    // Leave the field's value out of the builder's Debug output
+    pub(crate) redact: bool,
+    // Where each option was written, for errors about combining them
+    pub(crate) spans: Vec<(String, proc_macro2::Span)>,
+}
+
+impl FieldOptions {
+    // Where `builder(<option>)` was written on the field, if it was
+    pub(crate) fn span_of(&self, option: &str) -> Option<proc_macro2::Span> {
+        self.spans.iter().rev().find(|(name, _)| name == option).map(|(_, span)| *span)
+    }
+}
+
+// Chosen with `builder(merge = "...")` on an `each` field
+#[derive(Default, Clone, Copy, PartialEq)]
+pub(crate) enum MergePolicy {
+    // The higher priority elements are added after the lower priority ones
+    #[default]
+    Append,
+    // The higher priority elements, if there are any, replace the others
+    Replace,
+}
+
+pub(crate) fn parse_field_attributes(attrs: &[syn::Attribute], errors: &mut Errors) -> FieldOptions {
+    let mut options = FieldOptions::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("each") {
+                options.each = Some(parse_ident(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("name") {
+                options.name = Some(parse_ident(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("vis") {
+                options.vis = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.path.is_ident("private") {
+                options.vis = Some(syn::Visibility::Inherited);
+            } else if meta.path.is_ident("default") {
+                options.bare_default = !meta.input.peek(syn::Token![=]);
+                options.default = Some(if options.bare_default {
+                    syn::parse_quote!(::core::default::Default::default())
+                } else {
+                    meta.value()?.parse::<syn::LitStr>()?.parse()?
+                });
+            } else if meta.path.is_ident("setter") {
+                parse_setter_options(&meta, &mut options.setter)?;
+            } else if meta.path.is_ident("validate") {
+                options.validate = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.path.is_ident("merge") {
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                options.merge = Some(match lit.value().as_str() {
+                    "append" => MergePolicy::Append,
+                    "replace" => MergePolicy::Replace,
+                    _ => return Err(syn::Error::new(lit.span(), "expected \"append\" or \"replace\"")),
+                });
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("compute") {
+                options.compute = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.path.is_ident("optional") {
+                options.optional = true;
+            } else if meta.path.is_ident("required") {
+                options.required = true;
+            } else if meta.path.is_ident("redact") {
+                options.redact = true;
+            } else if meta.path.is_ident("env") {
+                options.env = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("env_separator") {
+                options.env_separator = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("sub_builder") {
+                options.sub_builder = Some(if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<syn::LitStr>()?.parse()?
+                } else {
+                    syn::parse_quote!(_)
+                });
+            } else {
+                return Err(syn::Error::new_spanned(&attr.meta, "expected `builder(each = \"...\")`"));
+            }
+            if let Some(ident) = meta.path.get_ident() {
+                options.spans.push((ident.to_string(), ident.span()));
+            }
+            Ok(())
+        });
+
+        if let Err(error) = result {
+            errors.push(error);
+        }
+    }
+
+    options
+}
+
+
+// Options inside `builder(setter(...))`. Unset ones fall back to what the
+// struct-level attribute says, and then to the plain setter behavior.
+#[derive(Default, Clone, Copy)]
+pub(crate) struct SetterOptions {
+    // Accept `impl Into<T>` instead of `T`
+    pub(crate) into: Option<bool>,
+    // Let an Option<T> field's setter take a T
+    pub(crate) strip_option: Option<bool>,
+    // Also generate a fallible `try_<field>` setter taking `impl TryInto<T>`
+    pub(crate) try_into: Option<bool>,
+}
+
+impl SetterOptions {
+    pub(crate) fn or(self, fallback: SetterOptions) -> SetterOptions {
+        SetterOptions {
+            into: self.into.or(fallback.into),
+            strip_option: self.strip_option.or(fallback.strip_option),
+            try_into: self.try_into.or(fallback.try_into),
+        }
+    }
+}
+
+fn parse_setter_options(meta: &syn::meta::ParseNestedMeta, options: &mut SetterOptions) -> syn::Result<()> {
+    meta.parse_nested_meta(|nested| {
+        if nested.path.is_ident("into") {
+            options.into = Some(parse_flag(&nested)?);
+        } else if nested.path.is_ident("strip_option") {
+            options.strip_option = Some(parse_flag(&nested)?);
+        } else if nested.path.is_ident("try_into") {
+            options.try_into = Some(parse_flag(&nested)?);
+        } else {
+            return Err(nested.error("expected `into`, `strip_option` or `try_into`"));
+        }
+        Ok(())
+    })
+}
+
+// Options inside `builder(build_fn(...))`. `error` and `validate` are the
+// same as the struct-level options of those names.
+fn parse_build_fn_options(meta: &syn::meta::ParseNestedMeta, options: &mut BuilderOptions) -> syn::Result<()> {
+    meta.parse_nested_meta(|nested| {
+        if nested.path.is_ident("name") {
+            options.build_fn = Some(parse_ident(&nested.value()?.parse()?)?);
+        } else if nested.path.is_ident("error") {
+            options.error = Some(nested.value()?.parse::<syn::LitStr>()?.parse()?);
+        } else if nested.path.is_ident("validate") {
+            options.validate = Some(nested.value()?.parse::<syn::LitStr>()?.parse()?);
+        } else {
+            return Err(nested.error("expected `name`, `error` or `validate`"));
+        }
+        Ok(())
+    })
+}
+
+// A switch that is either given bare, `into`, or as `into = false`
+fn parse_flag(meta: &syn::meta::ParseNestedMeta) -> syn::Result<bool> {
+    if meta.input.peek(syn::Token![=]) {
+        Ok(meta.value()?.parse::<syn::LitBool>()?.value)
+    } else {
+        Ok(true)
+    }
+}
@@ -0,0 +1,2057 @@
+extern crate proc_macro2;
+
+mod attrs;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, Data::{Enum, Struct, Union}, DeriveInput};
+use quote::{format_ident, quote, quote_spanned};
+use quote::ToTokens;
+
+use attrs::{parse_field_attributes, parse_struct_attributes, BuilderOptions, Constructor, Errors, FieldOptions, MergePolicy, Pattern};
+
+
+// A field of the input struct along with its builder options
+struct BuilderField<'a> {
+    // What the builder calls the field: its own name, `_0`, `_1`, ... for
+    // tuple struct fields, or what `builder(name = "...")` says
+    name: syn::Ident,
+    // How the field is accessed on the struct
+    member: syn::Member,
+    ty: &'a syn::Type,
+    // Visibility of the field's setters and other methods
+    vis: syn::Visibility,
+    // The field's `///` comments, repeated on its setters and getter
+    docs: Vec<syn::Attribute>,
+    options: FieldOptions,
+    // Set by a struct-level `builder(default)`
+    struct_default: bool,
+    // Set by a struct-level `builder(const)`
+    const_fn: bool,
+}
+
+impl BuilderField<'_> {
+    // What `build` uses for a field that was never set. The expression is
+    // only evaluated when it is needed. Required fields have no fallback.
+    fn fallback(&self) -> Option<proc_macro2::TokenStream> {
+        let member = &self.member;
+        if self.options.required {
+            None
+        } else if let Some(default) = &self.options.default {
+            Some(default.to_token_stream())
+        } else if self.struct_default {
+            Some(quote!(__default.#member))
+        } else if self.options.sub_builder.is_some() {
+            let ty = self.ty;
+            Some(build_sub_builder(self, quote!(<#ty>::builder())))
+        } else if self.options.each.is_some() || self.options.optional {
+            Some(quote!(::core::default::Default::default()))
+        } else if inner_type(self.ty, "Option").is_some() {
+            Some(quote!(::core::option::Option::None))
+        } else if inner_type(self.ty, "Vec").is_some() {
+            Some(quote!(::derive_builder::__alloc::vec::Vec::new()))
+        } else {
+            None
+        }
+    }
+
+    // Fields left out of the builder, which `build` fills in by itself
+    fn is_skipped(&self) -> bool {
+        self.options.skip || self.options.compute.is_some()
+    }
+
+    // Fields that `build` insists on being set
+    fn is_required(&self) -> bool {
+        self.fallback().is_none()
+    }
+
+    // Docs for one of the field's methods: the field's own, or the
+    // `fallback` if it has none
+    fn docs(&self, fallback: String) -> proc_macro2::TokenStream {
+        if self.docs.is_empty() {
+            quote!(#[doc = #fallback])
+        } else {
+            let docs = &self.docs;
+            quote!(#(#docs)*)
+        }
+    }
+
+    // Whether the setter of an Option<T> field takes a plain T. A
+    // `builder(required)` one takes the whole Option unless told otherwise,
+    // so that it can be set to None.
+    fn strips_option(&self) -> bool {
+        self.options.setter.strip_option.unwrap_or(!self.options.required) && inner_type(self.ty, "Option").is_some()
+    }
+
+    // What the builder keeps for the field: its sub-builder, if it has one,
+    // and otherwise a value of the field's type
+    fn storage_type(&self) -> &syn::Type {
+        self.options.sub_builder.as_ref().unwrap_or(self.ty)
+    }
+
+    // The type of value a setter stores: the T of a stripped Option<T>, or
+    // otherwise the field's own type
+    fn setter_value_type(&self) -> &syn::Type {
+        if self.strips_option() {
+            inner_type(self.ty, "Option").unwrap()
+        } else {
+            self.ty
+        }
+    }
+}
+
+// The docs of `builder()` and `build()`, which sort the fields by what
+// happens when they are not set
+fn generate_build_docs(fields: &[BuilderField], target: &Target) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let mut required = Vec::new();
+    let mut optional = Vec::new();
+    let mut repeated = Vec::new();
+    let mut defaulted = Vec::new();
+    let mut nested = Vec::new();
+    for field in fields {
+        let name = format!("`{}`", field.name);
+        if let Some(each_name) = &field.options.each {
+            repeated.push(format!("{} (one at a time with `{}`)", name, each_name));
+        } else if field.options.default.is_some() || field.struct_default {
+            defaulted.push(name);
+        } else if field.options.sub_builder.is_some() {
+            nested.push(format!("{} (with `{}_mut`)", name, field.name));
+        } else if field.is_required() {
+            required.push(name);
+        } else if inner_type(field.ty, "Option").is_some() || field.options.optional {
+            optional.push(name);
+        } else {
+            defaulted.push(name);
+        }
+    }
+
+    let required_kind = format!("Required fields, which `{}` fails without", target.build_fn);
+    let mut summary = Vec::new();
+    for (kind, names) in [
+        (required_kind.as_str(), required),
+        ("Optional fields, `None` unless set", optional),
+        ("Repeated fields, which collect every value they are given", repeated),
+        ("Defaulted fields, which fall back to their default value", defaulted),
+        ("Nested fields, which have a builder of their own", nested),
+    ] {
+        if !names.is_empty() {
+            summary.push(String::new());
+            summary.push(format!(" {}: {}.", kind, names.join(", ")));
+        }
+    }
+
+    let built = target.constructor().to_string().replace(' ', "");
+    let builder_doc = format!(" Starts a builder for [`{}`].", built);
+    let build_doc = format!(" Builds the [`{}`], or returns an error if it cannot be built.", built);
+    (
+        quote! {
+            #[doc = #builder_doc]
+            #(#[doc = #summary])*
+        },
+        quote! {
+            #[doc = #build_doc]
+            #(#[doc = #summary])*
+        },
+    )
+}
+
+
+// What a builder builds: the struct the derive is on, or one struct-like
+// variant of an enum
+struct Target<'a> {
+    ident: &'a syn::Ident,
+    generics: &'a syn::Generics,
+    // Visibility of the builder and of the methods that are not about one
+    // field, the struct's own unless `builder(vis = "...")` says otherwise
+    vis: syn::Visibility,
+    variant: Option<&'a syn::Ident>,
+    builder_name: syn::Ident,
+    // The associated fn that starts a builder, `builder` for structs
+    builder_fn: syn::Ident,
+    // The builder's method that finishes it
+    build_fn: syn::Ident,
+}
+
+impl Target<'_> {
+    // The path that the built value is constructed through
+    fn constructor(&self) -> proc_macro2::TokenStream {
+        let ident = self.ident;
+        match self.variant {
+            Some(variant) => quote!(#ident::#variant),
+            None => quote!(#ident),
+        }
+    }
+
+    fn builder_struct_doc(&self) -> String {
+        format!(" Builder for [`{}`], started with [`{}::{}`].", self.constructor().to_string().replace(' ', ""), self.ident, self.builder_fn)
+    }
+}
+
+// An error about combining the field's `builder(<option>)` with others,
+// pointing at where the option was written, or at the field's type if it
+// came from the struct's attribute
+fn option_error(options: &FieldOptions, option: &str, field: &syn::Field, message: String) -> syn::Error {
+    match options.span_of(option) {
+        Some(span) => syn::Error::new(span, message),
+        None => syn::Error::new_spanned(&field.ty, message),
+    }
+}
+
+fn collect_fields<'a>(fields: &'a syn::Fields, struct_options: &BuilderOptions, vis: &syn::Visibility, errors: &mut Errors) -> Vec<BuilderField<'a>> {
+    fields.iter().enumerate().map(|(index, field)| {
+        let mut options = parse_field_attributes(&field.attrs, errors);
+        options.setter = options.setter.or(struct_options.setter);
+
+        if options.each.is_some() && (collection_item(&field.ty).is_none() || inner_type(&field.ty, "Option").is_some()) {
+            errors.push(syn::Error::new_spanned(&field.ty, "`builder(each = \"...\")` requires a collection field"));
+            options.each = None;
+        }
+
+        if options.skip || options.compute.is_some() {
+            let attribute = if options.skip { "skip" } else { "compute" };
+            let conflict = if options.each.is_some() {
+                Some("each")
+            } else if options.validate.is_some() {
+                Some("validate")
+            } else if options.sub_builder.is_some() {
+                Some("sub_builder")
+            } else if options.env.is_some() {
+                Some("env")
+            } else if options.skip && options.compute.is_some() {
+                Some("compute")
+            } else if options.optional {
+                Some("optional")
+            } else if options.required {
+                Some("required")
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                errors.push(option_error(&options, conflict, field, format!("`builder({})` cannot be combined with `{}`", attribute, conflict)));
+            }
+            options.each = None;
+            options.validate = None;
+            options.sub_builder = None;
+            options.env = None;
+            options.optional = false;
+            options.required = false;
+        }
+
+        if options.required {
+            let conflict = if options.optional {
+                Some("optional")
+            } else if options.default.is_some() {
+                Some("default")
+            } else if options.each.is_some() {
+                Some("each")
+            } else if options.sub_builder.is_some() {
+                Some("sub_builder")
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                errors.push(option_error(&options, conflict, field, format!("`builder(required)` cannot be combined with `{}`", conflict)));
+                options.required = false;
+            }
+        }
+
+        if let Some(env) = &options.env {
+            if struct_options.typestate {
+                errors.push(syn::Error::new(env.span(), "typestate builders cannot be read from the environment"));
+                options.env = None;
+            }
+        }
+
+        if options.env.is_some() && options.optional {
+            errors.push(option_error(&options, "optional", field, "`builder(optional)` fields cannot be read from the environment".to_owned()));
+            options.env = None;
+        }
+
+        if options.merge.is_some() && options.each.is_none() {
+            errors.push(option_error(&options, "merge", field, "`builder(merge = \"...\")` only applies to `each` fields".to_owned()));
+            options.merge = None;
+        }
+
+        if let Some(sub_builder) = &mut options.sub_builder {
+            if let syn::Type::Infer(_) = sub_builder {
+                match sub_builder_type(&field.ty) {
+                    Some(ty) => *sub_builder = ty,
+                    None => {
+                        errors.push(syn::Error::new_spanned(&field.ty, "cannot infer the builder of this type, name it with `builder(sub_builder = \"...\")`"));
+                        options.sub_builder = None;
+                    }
+                }
+            }
+        }
+        if options.sub_builder.is_some() {
+            let conflict = if options.each.is_some() {
+                Some("each")
+            } else if options.validate.is_some() {
+                Some("validate")
+            } else if options.env.is_some() {
+                Some("env")
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                errors.push(option_error(&options, conflict, field, format!("`builder(sub_builder)` cannot be combined with `{}`", conflict)));
+                options.sub_builder = None;
+            }
+        }
+
+        // What a const setter or `build_const` would have to call is not
+        // const. That includes a field type's Default, so skipped and
+        // defaulted fields need a `default = "..."` that is.
+        if struct_options.const_fn {
+            let conflict = if options.each.is_some() {
+                Some(("each", "`builder(const)` cannot be combined with `each`"))
+            } else if options.sub_builder.is_some() {
+                Some(("sub_builder", "`builder(const)` cannot be combined with `sub_builder`"))
+            } else if options.validate.is_some() {
+                Some(("validate", "`builder(const)` cannot be combined with `validate`"))
+            } else if options.compute.is_some() {
+                Some(("compute", "`builder(const)` cannot be combined with `compute`"))
+            } else if options.setter.into == Some(true) {
+                // `setter(into)` was written as part of `setter(...)`
+                Some(("setter", "`builder(const)` cannot be combined with `setter(into)`"))
+            } else if options.optional {
+                Some(("optional", "`builder(const)` cannot be combined with `optional`"))
+            } else if options.bare_default {
+                Some(("default", "`builder(const)` needs the default to be given, as `default = \"...\"`"))
+            } else if options.skip && options.default.is_none() {
+                Some(("skip", "`builder(const)` needs a skipped field's default to be given, as `default = \"...\"`"))
+            } else {
+                None
+            };
+            if let Some((option, message)) = conflict {
+                errors.push(option_error(&options, option, field, message.to_owned()));
+            }
+            // A field whose value can't be worked out in a const fn is left
+            // for the caller to set instead
+            options.each = None;
+            options.sub_builder = None;
+            options.validate = None;
+            options.compute = None;
+            options.setter.into = None;
+            options.optional = false;
+            if options.bare_default {
+                options.default = None;
+            }
+            options.skip &= options.default.is_some();
+        }
+
+        let member = match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(syn::Index::from(index)),
+        };
+        let name = match (options.name.take(), &field.ident) {
+            (Some(name), _) => name,
+            (None, Some(ident)) => ident.clone(),
+            (None, None) => format_ident!("_{}", index),
+        };
+
+        BuilderField {
+            name,
+            member,
+            ty: &field.ty,
+            vis: options.vis.take().unwrap_or_else(|| vis.clone()),
+            docs: field.attrs.iter().filter(|attr| attr.path().is_ident("doc")).cloned().collect(),
+            options,
+            struct_default: struct_options.default,
+            const_fn: struct_options.const_fn,
+        }
+    }).collect()
+}
+
+
+fn generate_member_variables_of_builder(fields: &[BuilderField]) -> proc_macro2::TokenStream {
+    let fields = fields.iter().map(|field| {
+        let name = &field.name;
+        let ty = field.storage_type();
+
+        quote! {
+            #name: ::core::option::Option<#ty>
+        }
+    });
+
+    quote! {
+        #(#fields,)*
+    }
+}
+
+
+fn generate_setter_functions_of_builder(fields: &[BuilderField], pattern: Pattern, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    let (receiver, returns) = match pattern {
+        Pattern::Immutable | Pattern::Mutable => (quote!(&mut self), quote!(&mut Self)),
+        Pattern::Owned => (quote!(mut self), quote!(Self)),
+    };
+    let setters = fields.iter().map(|field| {
+        let setters = generate_field_setters(field, receiver.clone(), returns.clone());
+        let mutator = generate_field_mutator(field, receiver.clone(), returns.clone());
+        quote! {
+            #setters
+            #mutator
+        }
+    });
+    let introspection = fields.iter().map(|field| {
+        generate_field_introspection(field, receiver.clone(), returns.clone())
+    });
+    let unset_fields = generate_unset_fields(fields, vis);
+
+    quote! {
+        #(#setters)*
+
+        #(#introspection)*
+
+        #unset_fields
+    }
+}
+
+
+// Lets the caller look at a partially filled builder: whether a field is
+// set, what it is set to, and a way to unset it again
+fn generate_field_introspection(field: &BuilderField, receiver: proc_macro2::TokenStream, returns: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let (name, vis) = (&field.name, &field.vis);
+    let ty = field.storage_type();
+    let is_set = format_ident!("{}_is_set", name);
+    let get = format_ident!("get_{}", name);
+    let clear = format_ident!("clear_{}", name);
+    let is_set_doc = format!(" Whether `{}` has been set.", name);
+    let get_docs = field.docs(format!(" What `{}` has been set to, if anything.", name));
+    let clear_doc = format!(" Unsets `{}`.", name);
+    // An Option field whose setter takes a plain value is got as one too,
+    // and counts as unset when it was set to None some other way
+    let (value_ty, value) = if field.strips_option() {
+        (field.setter_value_type(), quote!(self.#name.as_ref().and_then(::core::option::Option::as_ref)))
+    } else {
+        (ty, quote!(self.#name.as_ref()))
+    };
+
+    quote! {
+        #[doc = #is_set_doc]
+        #vis fn #is_set(&self) -> bool {
+            self.#name.is_some()
+        }
+
+        #get_docs
+        #vis fn #get(&self) -> ::core::option::Option<&#value_ty> {
+            #value
+        }
+
+        #[doc = #clear_doc]
+        #vis fn #clear(#receiver) -> #returns {
+            self.#name = ::core::option::Option::None;
+            self
+        }
+    }
+}
+
+// `unset_fields` names the required fields that `build` would still
+// complain about, e.g. for a UI to prompt for them
+fn generate_unset_fields(fields: &[BuilderField], vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    let unset = fields.iter().filter(|field| field.is_required()).map(|field| {
+        let name = &field.name;
+        let name_as_str = name_as_str(name);
+        quote! {
+            if self.#name.is_none() {
+                unset.push(#name_as_str);
+            }
+        }
+    });
+
+    quote! {
+        /// The required fields that have not been set yet.
+        #vis fn unset_fields(&self) -> ::derive_builder::__alloc::vec::Vec<&'static str> {
+            let mut unset = ::derive_builder::__alloc::vec::Vec::new();
+            #(#unset)*
+            unset
+        }
+    }
+}
+
+
+// The setters of a field that does not change the builder's type: the plain
+// or `each` setter, plus a fallible `try_` variant if one was asked for
+fn generate_field_setters(field: &BuilderField, receiver: proc_macro2::TokenStream, returns: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if let Some(each_name) = &field.options.each {
+        return generate_each_setters(field, each_name, receiver, returns);
+    }
+
+    let (setter_name, vis) = (&field.name, &field.vis);
+    let docs = field.docs(format!(" Sets `{}`.", setter_name));
+    let value_ty = field.setter_value_type();
+    let argument = format_ident!("argument");
+    let (argument_ty, value) = setter_argument(field, value_ty, &argument);
+    let (qualifiers, store) = if field.const_fn {
+        (quote!(#[allow(forgetting_copy_types)] #vis const), store_value_const(field, value))
+    } else {
+        (quote!(#vis), store_value(field, value))
+    };
+
+    let try_setter = if field.options.setter.try_into.unwrap_or(false) {
+        let try_name = format_ident!("try_{}", setter_name);
+        let store = store_value(field, quote!(argument));
+        quote! {
+            #docs
+            #vis fn #try_name<__Value: ::core::convert::TryInto<#value_ty>>(#receiver, argument: __Value) -> ::core::result::Result<#returns, <__Value as ::core::convert::TryInto<#value_ty>>::Error> {
+                let argument = ::core::convert::TryInto::try_into(argument)?;
+                #store
+                ::core::result::Result::Ok(self)
+            }
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    // Hands out the field's sub-builder to be filled in place, starting a
+    // fresh one if there is none yet
+    let sub_builder_accessor = field.options.sub_builder.as_ref().map(|sub_builder| {
+        let accessor_name = format_ident!("{}_mut", setter_name);
+        let ty = field.ty;
+        quote! {
+            #docs
+            #vis fn #accessor_name(&mut self) -> &mut #sub_builder {
+                self.#setter_name.get_or_insert_with(<#ty>::builder)
+            }
+        }
+    });
+
+    quote! {
+        #docs
+        #qualifiers fn #setter_name(#receiver, argument: #argument_ty) -> #returns {
+            #store
+            self
+        }
+
+        #try_setter
+
+        #sub_builder_accessor
+    }
+}
+
+// `with_<field>`, which changes the field's value in place with a closure,
+// starting from the type's Default if it was not set yet, or from a fresh
+// builder for a `sub_builder` field. The Default bound is higher-ranked so
+// that field types without one only go without this method.
+fn generate_field_mutator(field: &BuilderField, receiver: proc_macro2::TokenStream, returns: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let (name, vis) = (&field.name, &field.vis);
+    let mutator_name = format_ident!("with_{}", name);
+    let docs = field.docs(format!(" Changes `{}` in place, starting from its default if it is not set.", name));
+    let stored = field.storage_type();
+    let (initial, bound) = if field.options.sub_builder.is_some() {
+        let ty = field.ty;
+        (quote!(<#ty>::builder), proc_macro2::TokenStream::new())
+    } else {
+        let (bound_ty, lifetimes) = higher_ranked_type(stored);
+        (quote!(::core::default::Default::default), quote! {
+            where
+                for<'__builder, #(#lifetimes),*> #bound_ty: ::core::default::Default
+        })
+    };
+
+    quote! {
+        #docs
+        #vis fn #mutator_name<__Edit: ::core::ops::FnOnce(&mut #stored)>(#receiver, edit: __Edit) -> #returns
+        #bound
+        {
+            edit(::core::option::Option::get_or_insert_with(&mut self.#name, #initial));
+            self
+        }
+    }
+}
+
+// The one-at-a-time setter of an `each` field, which adds a single item (or
+// a key and a value for maps) to the collection, and `<field>_extend` which
+// adds any number of them at once
+fn generate_each_setters(field: &BuilderField, each_name: &syn::Ident, receiver: proc_macro2::TokenStream, returns: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let (name, vis) = (&field.name, &field.vis);
+    let docs = field.docs(format!(" Adds one value to `{}`.", name));
+    let extend_docs = field.docs(format!(" Adds every value to `{}`.", name));
+    let extend_name = format_ident!("{}_extend", name);
+    let collection = quote! {
+        self.#name.get_or_insert_with(::core::default::Default::default)
+    };
+
+    match collection_item(field.ty).unwrap() {
+        CollectionItem::Pair(key_ty, value_ty) => {
+            let (key_argument_ty, key) = setter_argument(field, &key_ty, &format_ident!("key"));
+            let (value_argument_ty, value) = setter_argument(field, &value_ty, &format_ident!("value"));
+            quote! {
+                #docs
+                #vis fn #each_name(#receiver, key: #key_argument_ty, value: #value_argument_ty) -> #returns {
+                    ::core::iter::Extend::extend(#collection, ::core::iter::once((#key, #value)));
+                    self
+                }
+
+                #extend_docs
+                #vis fn #extend_name(#receiver, items: impl ::core::iter::IntoIterator<Item = (#key_ty, #value_ty)>) -> #returns {
+                    ::core::iter::Extend::extend(#collection, items);
+                    self
+                }
+            }
+        }
+        CollectionItem::Single(item_ty) => {
+            let argument = format_ident!("argument");
+            let (argument_ty, value) = setter_argument(field, &item_ty, &argument);
+
+            let try_setter = if field.options.setter.try_into.unwrap_or(false) {
+                let try_name = format_ident!("try_{}", each_name);
+                quote! {
+                    #docs
+                    #vis fn #try_name<__Value: ::core::convert::TryInto<#item_ty>>(#receiver, argument: __Value) -> ::core::result::Result<#returns, <__Value as ::core::convert::TryInto<#item_ty>>::Error> {
+                        let argument = ::core::convert::TryInto::try_into(argument)?;
+                        ::core::iter::Extend::extend(#collection, ::core::iter::once(argument));
+                        ::core::result::Result::Ok(self)
+                    }
+                }
+            } else {
+                proc_macro2::TokenStream::new()
+            };
+
+            quote! {
+                #docs
+                #vis fn #each_name(#receiver, argument: #argument_ty) -> #returns {
+                    ::core::iter::Extend::extend(#collection, ::core::iter::once(#value));
+                    self
+                }
+
+                #try_setter
+
+                #extend_docs
+                #vis fn #extend_name(#receiver, items: impl ::core::iter::IntoIterator<Item = #item_ty>) -> #returns {
+                    ::core::iter::Extend::extend(#collection, items);
+                    self
+                }
+            }
+        }
+    }
+}
+
+// The parameter type of a setter that ends up with a `value_ty`, and the
+// expression that turns the `argument` into one
+fn setter_argument(field: &BuilderField, value_ty: impl ToTokens, argument: &syn::Ident) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    if field.options.setter.into.unwrap_or(false) {
+        (quote!(impl ::core::convert::Into<#value_ty>), quote!(::core::convert::Into::into(#argument)))
+    } else {
+        (quote!(#value_ty), quote!(#argument))
+    }
+}
+
+// Stores a `value` of the field's setter type into the builder
+fn store_value(field: &BuilderField, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let name = &field.name;
+    if field.strips_option() {
+        quote! {
+            self.#name = ::core::option::Option::Some(::core::option::Option::Some(#value));
+        }
+    } else if field.options.sub_builder.is_some() {
+        quote! {
+            self.#name = ::core::option::Option::Some(::core::convert::From::from(#value));
+        }
+    } else {
+        quote! {
+            self.#name = ::core::option::Option::Some(#value);
+        }
+    }
+}
+
+// `store_value` for a const setter. Const fns cannot run destructors, so the
+// value being replaced, if any, is forgotten rather than dropped.
+fn store_value_const(field: &BuilderField, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let name = &field.name;
+    let stored = if field.strips_option() {
+        quote!(::core::option::Option::Some(::core::option::Option::Some(#value)))
+    } else {
+        quote!(::core::option::Option::Some(#value))
+    };
+    quote! {
+        ::core::mem::forget(::core::mem::replace(&mut self.#name, #stored));
+    }
+}
+
+
+fn generate_default_setters_for_builder(fields: &[BuilderField], builder_name: &syn::Ident, phantom: bool) -> proc_macro2::TokenStream {
+    let default_setters = fields.iter().map(|field| {
+        let name = &field.name;
+        quote! {
+            #name: ::core::option::Option::None
+        }
+    });
+    let phantom = phantom.then(|| quote!(__phantom: ::core::marker::PhantomData,));
+
+    quote! {
+        #builder_name{#(#default_setters,)* #phantom}
+    }
+}
+
+
+// The struct literal `build` returns. A `build(&mut self)` takes the values
+// out of the builder, so its sub-builders, the only fields that can still
+// fail, are built from clones first; otherwise a failure would lose
+// whatever had been taken before it.
+fn generate_setters_for_final_constructor(fields: &[BuilderField], skipped: &[BuilderField], constructor: proc_macro2::TokenStream, pattern: Pattern) -> proc_macro2::TokenStream {
+    let clone = |name: &syn::Ident| quote!(::core::clone::Clone::clone(&self.#name));
+    let prebuilt: Vec<proc_macro2::TokenStream> = fields.iter().filter(|field| pattern == Pattern::Mutable && field.options.sub_builder.is_some()).map(|field| {
+        let local = format_ident!("__built_{}", field.name);
+        let resolved = resolve_field(field, clone(&field.name));
+        quote! {
+            let #local = #resolved;
+        }
+    }).collect();
+
+    let builder = fields.iter().map(|field| {
+        let name = &field.name;
+        let member = &field.member;
+        if pattern == Pattern::Mutable && field.options.sub_builder.is_some() {
+            let local = format_ident!("__built_{}", name);
+            return quote! {
+                #member: {
+                    self.#name = ::core::option::Option::None;
+                    #local
+                }
+            };
+        }
+        let stored = match pattern {
+            // Sub-builders are cloned like everything else, since the inner
+            // builder's `build` may need to take it by value
+            Pattern::Immutable => clone(name),
+            Pattern::Mutable => quote!(self.#name.take()),
+            Pattern::Owned => quote!(self.#name),
+        };
+        let resolved = resolve_field(field, stored);
+
+        quote! {
+            #member: #resolved
+        }
+    });
+    let skipped = generate_skipped_fields(skipped);
+
+    if prebuilt.is_empty() {
+        return quote! {
+            #constructor{#(#builder,)* #skipped}
+        };
+    }
+    quote! {
+        {
+            #(#prebuilt)*
+            #constructor{#(#builder,)* #skipped}
+        }
+    }
+}
+
+// Fields that are not part of the builder start out as their `default`, or
+// the type's Default. Computed fields too, until they are computed, so a
+// type without Default is reported at the field rather than at the derive.
+fn generate_skipped_fields(skipped: &[BuilderField]) -> proc_macro2::TokenStream {
+    let initializers = skipped.iter().map(|field| {
+        let member = &field.member;
+        let ty = field.ty;
+        let default = match &field.options.default {
+            Some(default) => default.to_token_stream(),
+            None => quote_spanned!(syn::spanned::Spanned::span(ty)=> <#ty as ::core::default::Default>::default()),
+        };
+        quote! {
+            #member: #default
+        }
+    });
+
+    quote! {
+        #(#initializers,)*
+    }
+}
+
+// Returns the `constructor`'s value from `build`, after running the
+// `builder(compute = "...")` functions on it in the order of their fields.
+// Each one sees the values computed before it. The struct's `post_build`
+// runs last, on the finished value.
+fn finish_build(constructor: proc_macro2::TokenStream, skipped: &[BuilderField], post_build: Option<&syn::Path>) -> proc_macro2::TokenStream {
+    let computed: Vec<proc_macro2::TokenStream> = skipped.iter().filter_map(|field| {
+        let member = &field.member;
+        let compute = field.options.compute.as_ref()?;
+        Some(quote! {
+            built.#member = #compute(&built);
+        })
+    }).collect();
+
+    if computed.is_empty() && post_build.is_none() {
+        return quote! {
+            ::core::result::Result::Ok(#constructor)
+        };
+    }
+
+    let post_build = post_build.map(|post_build| quote! {
+        #post_build(&mut built);
+    });
+    quote! {
+        let mut built = #constructor;
+        #(#computed)*
+        #post_build
+        ::core::result::Result::Ok(built)
+    }
+}
+
+// `build_const`, for `builder(const)`. A const fn must not leave anything to
+// be dropped that might need a destructor, including a partly moved builder
+// or an Option that turned out to be None, so every value is taken out of
+// the builder and whatever is left over is forgotten. A missing field is a
+// plain panic, which is an error at compile time when the builder is
+// evaluated in a const or static.
+fn generate_build_const(fields: &[BuilderField], skipped: &[BuilderField], target: &Target) -> proc_macro2::TokenStream {
+    let vis = &target.vis;
+    let (_, ty_generics, _) = target.generics.split_for_impl();
+    let struct_name = target.ident;
+    let constructor = target.constructor();
+    let taken = fields.iter().map(|field| {
+        let name = &field.name;
+        let fallback = field.fallback().unwrap_or_else(|| {
+            let message = format!("missing required field `{}`", name_as_str(name));
+            quote!(::core::panic!(#message))
+        });
+        quote! {
+            let #name = ::core::option::Option::take(&mut self.#name);
+            let #name = if ::core::option::Option::is_some(&#name) {
+                ::core::option::Option::unwrap(#name)
+            } else {
+                ::core::mem::forget(#name);
+                #fallback
+            };
+        }
+    });
+    let values = fields.iter().map(|field| {
+        let (name, member) = (&field.name, &field.member);
+        quote! { #member: #name }
+    });
+    let skipped_fields = generate_skipped_fields(skipped);
+    let doc = format!(" Builds the [`{}`] in a const context, e.g. the value of a `static`. A missing required field panics, which fails compilation when the builder is evaluated at compile time.", constructor.to_string().replace(' ', ""));
+
+    quote! {
+        #[doc = #doc]
+        #[allow(forgetting_copy_types)]
+        #vis const fn build_const(mut self) -> #struct_name #ty_generics {
+            #(#taken)*
+            ::core::mem::forget(self);
+            #constructor {
+                #(#values,)*
+                #skipped_fields
+            }
+        }
+    }
+}
+
+// The value a field ends up with in `build`, out of the `stored` Option
+fn resolve_field(field: &BuilderField, stored: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    // Required fields were checked by the validators already, but the arm
+    // still reports them rather than panicking, which no_std crates may not
+    // be able to do
+    let fallback = field.fallback().unwrap_or_else(|| {
+        let name_as_str = name_as_str(&field.name);
+        quote! {
+            return ::core::result::Result::Err(::core::convert::From::from(
+                ::derive_builder::BuilderError::MissingFields(::derive_builder::__alloc::vec![
+                    ::derive_builder::__alloc::string::String::from(#name_as_str),
+                ]),
+            ))
+        }
+    });
+    let value = if field.options.sub_builder.is_some() {
+        // The sub-builder may be held by value, and then needs to be mutable
+        // for a `build(&mut self)`
+        let built = build_sub_builder(field, quote!(value));
+        quote! {
+            #[allow(unused_mut)]
+            ::core::option::Option::Some(mut value) => #built,
+        }
+    } else {
+        quote! {
+            ::core::option::Option::Some(value) => value,
+        }
+    };
+
+    quote! {
+        match #stored {
+            #value
+            ::core::option::Option::None => #fallback,
+        }
+    }
+}
+
+// Builds a field's `sub_builder`, returning early from the outer `build` with
+// the inner error moved to the field's path. The inner builder is finished
+// with its hidden `__build`, which calls whatever its `build_fn` is called.
+fn build_sub_builder(field: &BuilderField, sub_builder: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let name_as_str = name_as_str(&field.name);
+    quote! {
+        match #sub_builder.__build() {
+            ::core::result::Result::Ok(value) => value,
+            ::core::result::Result::Err(error) => return ::core::result::Result::Err(::core::convert::From::from(
+                ::derive_builder::BuilderError::in_field(error, #name_as_str),
+            )),
+        }
+    }
+}
+
+fn generate_validators(fields: &[BuilderField]) -> proc_macro2::TokenStream {
+    let validators = fields.iter().filter(|field| field.is_required()).map(|field| {
+        let name = &field.name;
+        let name_as_str = name_as_str(name);
+
+        quote! {
+            if self.#name.is_none() {
+                missing.push(::derive_builder::__alloc::string::String::from(#name_as_str));
+            }
+        }
+    });
+
+    quote! {
+        let mut missing: ::derive_builder::__alloc::vec::Vec<::derive_builder::__alloc::string::String> = ::derive_builder::__alloc::vec::Vec::new();
+        #(#validators)*
+        if !missing.is_empty() {
+            return ::core::result::Result::Err(::core::convert::From::from(
+                ::derive_builder::BuilderError::MissingFields(missing),
+            ));
+        }
+    }
+}
+
+// Runs the `builder(validate = "...")` hooks: first each field's on the
+// value it was set to, then the struct's on the whole builder. Nothing has
+// been moved out of the builder yet, so a rejected build leaves it intact.
+// `raw_required` is set for typestate builders, whose required fields are
+// stored as plain values rather than Options.
+fn generate_validation_hooks(fields: &[BuilderField], options: &BuilderOptions, raw_required: bool) -> proc_macro2::TokenStream {
+    let field_hooks = fields.iter().filter_map(|field| {
+        let validate = field.options.validate.as_ref()?;
+        let name = &field.name;
+        let name_as_str = name_as_str(name);
+        let check = quote! {
+            if let ::core::result::Result::Err(message) = #validate(value) {
+                return ::core::result::Result::Err(::core::convert::From::from(
+                    ::derive_builder::BuilderError::Validation {
+                        field: ::core::option::Option::Some(::derive_builder::__alloc::string::String::from(#name_as_str)),
+                        message,
+                    },
+                ));
+            }
+        };
+
+        Some(if raw_required && field.is_required() {
+            quote! {
+                let value = &self.#name;
+                #check
+            }
+        } else {
+            quote! {
+                if let ::core::option::Option::Some(value) = &self.#name {
+                    #check
+                }
+            }
+        })
+    });
+
+    let struct_hook = options.validate.as_ref().map(|validate| {
+        quote! {
+            if let ::core::result::Result::Err(message) = #validate(&self) {
+                return ::core::result::Result::Err(::core::convert::From::from(
+                    ::derive_builder::BuilderError::Validation {
+                        field: ::core::option::Option::None,
+                        message,
+                    },
+                ));
+            }
+        }
+    });
+
+    quote! {
+        #(#field_hooks)*
+        #struct_hook
+    }
+}
+
+
+// `from_env` reads fields from environment variables: every field with
+// `builder(env = "...")`, and with a struct-level `env_prefix` every other
+// one as well from `<prefix><FIELD>`. Lists are split on the separator and
+// each item is parsed on its own. Variables that are not set leave their
+// field unset for `build` to deal with.
+fn generate_from_env(fields: &[BuilderField], options: &BuilderOptions, target: &Target) -> proc_macro2::TokenStream {
+    let (struct_name, generics, builder_name, builder_fn, vis) = (target.ident, target.generics, &target.builder_name, &target.builder_fn, &target.vis);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut bounds = Vec::new();
+
+    let readers: Vec<proc_macro2::TokenStream> = fields.iter().filter_map(|field| {
+        let name = &field.name;
+        let variable = match (&field.options.env, &options.env_prefix) {
+            (Some(env), _) => env.value(),
+            // An `optional` field's type can't be seen through to parse it
+            (None, Some(prefix)) if field.options.sub_builder.is_none() && !field.options.optional => {
+                format!("{}{}", prefix, name_as_str(name).to_uppercase())
+            }
+            _ => return None,
+        };
+
+        let mut parse = |ty: proc_macro2::TokenStream, value: proc_macro2::TokenStream| {
+            bounds.push(ty.clone());
+            quote!(::derive_builder::parse_env_var::<#ty>(#variable, #value)?)
+        };
+        let list_item = if field.options.each.is_some() {
+            collection_item(field.ty)
+        } else {
+            inner_type(field.ty, "Vec").map(|item| CollectionItem::Single(item.to_token_stream()))
+        };
+        let value = if let Some(item) = list_item {
+            let separator = field.options.env_separator.as_deref().or(options.env_separator.as_deref()).unwrap_or(",");
+            let parsed = match item {
+                CollectionItem::Single(item_ty) => parse(item_ty, quote!(item)),
+                CollectionItem::Pair(key_ty, value_ty) => {
+                    let key = parse(key_ty, quote!(key));
+                    let value = parse(value_ty, quote!(value));
+                    quote! {
+                        match item.split_once('=') {
+                            ::core::option::Option::Some((key, value)) => (#key, #value),
+                            ::core::option::Option::None => return ::core::result::Result::Err(::derive_builder::EnvError::Parse {
+                                variable: ::derive_builder::__alloc::string::String::from(#variable),
+                                value: ::derive_builder::__alloc::string::String::from(item),
+                                message: ::derive_builder::__alloc::string::String::from("expected `key=value`"),
+                            }),
+                        }
+                    }
+                }
+            };
+            let ty = field.ty;
+            quote! {
+                {
+                    let mut items: #ty = ::core::default::Default::default();
+                    for item in value.split(#separator).filter(|item| !item.is_empty()) {
+                        ::core::iter::Extend::extend(&mut items, ::core::iter::once(#parsed));
+                    }
+                    items
+                }
+            }
+        } else if let Some(inner) = inner_type(field.ty, "Option") {
+            let parsed = parse(inner.to_token_stream(), quote!(&value));
+            quote!(::core::option::Option::Some(#parsed))
+        } else {
+            parse(field.ty.to_token_stream(), quote!(&value))
+        };
+
+        Some(quote! {
+            if let ::core::option::Option::Some(value) = ::derive_builder::EnvSource::var(&source, #variable)? {
+                builder.#name = ::core::option::Option::Some(#value);
+            }
+        })
+    }).collect();
+
+    if readers.is_empty() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    // Parsing only needs spelling out for types that depend on the struct's
+    // type parameters
+    let bounds: Vec<proc_macro2::TokenStream> = bounds.into_iter().filter(|ty| mentions_type_param(ty, generics)).collect();
+
+    quote! {
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            ::derive_builder::__with_std! {
+                /// Starts a builder with the fields read from the environment
+                /// of the process.
+                #vis fn from_env() -> ::core::result::Result<Self, ::derive_builder::EnvError>
+                where
+                    #(#bounds: ::core::str::FromStr, <#bounds as ::core::str::FromStr>::Err: ::core::fmt::Display,)*
+                {
+                    Self::from_env_with(::derive_builder::ProcessEnv)
+                }
+            }
+
+            /// Starts a builder with the fields read from `source`, e.g. a map
+            /// of variables or a closure that looks them up.
+            #vis fn from_env_with(source: impl ::derive_builder::EnvSource) -> ::core::result::Result<Self, ::derive_builder::EnvError>
+            where
+                #(#bounds: ::core::str::FromStr, <#bounds as ::core::str::FromStr>::Err: ::core::fmt::Display,)*
+            {
+                let mut builder = #struct_name::#builder_fn();
+                #(#readers)*
+                ::core::result::Result::Ok(builder)
+            }
+        }
+    }
+}
+
+
+// `<Struct>Partial`, asked for with `builder(partial)`, has an Option of
+// every field, for configuration that is put together from several
+// sources. `merge` layers one partial over another, and `apply_to` writes
+// whatever is set onto a finished value. `phantom` is set when skipped
+// fields may have been the only users of some of the struct's parameters.
+fn generate_partial(fields: &[BuilderField], target: &Target, phantom: bool) -> proc_macro2::TokenStream {
+    let (struct_name, generics, vis) = (target.ident, target.generics, &target.vis);
+    let partial_name = format_ident!("{}Partial", struct_name);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (phantom_field, phantom_value) = if phantom {
+        (
+            quote! {
+                #[doc(hidden)]
+                pub __phantom: ::core::marker::PhantomData<fn() -> #struct_name #ty_generics>,
+            },
+            quote!(__phantom: ::core::marker::PhantomData,),
+        )
+    } else {
+        (proc_macro2::TokenStream::new(), proc_macro2::TokenStream::new())
+    };
+
+    let partial_fields = fields.iter().map(|field| {
+        let name = &field.name;
+        let ty = field.ty;
+        let docs = field.docs(format!(" `{}`, if it is set.", name));
+        quote! {
+            #docs
+            pub #name: ::core::option::Option<#ty>
+        }
+    });
+    let partial_doc = format!(" Any of the fields of [`{}`], for layering configuration from several sources.", struct_name);
+    let unset = fields.iter().map(|field| {
+        let name = &field.name;
+        quote! { #name: ::core::option::Option::None }
+    });
+
+    let appends = |field: &BuilderField| field.options.each.is_some() && field.options.merge.unwrap_or_default() == MergePolicy::Append;
+    let merged = fields.iter().map(|field| {
+        let name = &field.name;
+        if appends(field) {
+            quote! {
+                #name: match (self.#name, higher_priority.#name) {
+                    (::core::option::Option::Some(mut lower), ::core::option::Option::Some(higher)) => {
+                        ::core::iter::Extend::extend(&mut lower, higher);
+                        ::core::option::Option::Some(lower)
+                    }
+                    (lower, higher) => ::core::option::Option::or(higher, lower),
+                }
+            }
+        } else {
+            quote! {
+                #name: ::core::option::Option::or(higher_priority.#name, self.#name)
+            }
+        }
+    });
+    let applied = fields.iter().map(|field| {
+        let (name, member) = (&field.name, &field.member);
+        if appends(field) {
+            quote! {
+                if let ::core::option::Option::Some(value) = self.#name {
+                    ::core::iter::Extend::extend(&mut target.#member, value);
+                }
+            }
+        } else {
+            quote! {
+                if let ::core::option::Option::Some(value) = self.#name {
+                    target.#member = value;
+                }
+            }
+        }
+    });
+
+    quote! {
+        #[doc = #partial_doc]
+        #vis struct #partial_name #generics #where_clause {
+            #(#partial_fields,)*
+            #phantom_field
+        }
+
+        impl #impl_generics ::core::default::Default for #partial_name #ty_generics #where_clause {
+            fn default() -> Self {
+                #partial_name {
+                    #(#unset,)*
+                    #phantom_value
+                }
+            }
+        }
+
+        impl #impl_generics #partial_name #ty_generics #where_clause {
+            /// Combines two partials. Fields set in `higher_priority` win,
+            /// and the rest are kept from `self`.
+            #vis fn merge(self, higher_priority: Self) -> Self {
+                #partial_name {
+                    #(#merged,)*
+                    #phantom_value
+                }
+            }
+
+            /// Overwrites `target`'s fields with every field that is set here.
+            #vis fn apply_to(self, target: &mut #struct_name #ty_generics) {
+                #(#applied)*
+            }
+        }
+    }
+}
+
+
+// `to_builder()` and `From<Struct>` turn a finished value back into a builder
+// with every field set, so a modified copy only needs the setters that change
+// something. `builder_ty` is the builder type the conversion produces, which
+// for typestate builders is the state with every required field set.
+// `phantom` is set for builders with a `__phantom` field.
+fn generate_conversions_to_builder(fields: &[BuilderField], target: &Target, builder_ty: &proc_macro2::TokenStream, typestate: bool, phantom: bool) -> proc_macro2::TokenStream {
+    let (struct_name, generics, builder_name, vis) = (target.ident, target.generics, &target.builder_name, &target.vis);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let prefill = |value: &dyn Fn(&BuilderField) -> proc_macro2::TokenStream| {
+        let prefilled = fields.iter().map(|field| {
+            let name = &field.name;
+            let value = value(field);
+            if typestate && field.is_required() {
+                quote! { #name: #value }
+            } else {
+                quote! { #name: ::core::option::Option::Some(#value) }
+            }
+        });
+        let phantom = if phantom {
+            quote! { __phantom: ::core::marker::PhantomData, }
+        } else {
+            proc_macro2::TokenStream::new()
+        };
+        quote! {
+            #builder_name {
+                #(#prefilled,)*
+                #phantom
+            }
+        }
+    };
+    let into_storage = |field: &BuilderField, value: proc_macro2::TokenStream| {
+        if field.options.sub_builder.is_some() {
+            quote!(::core::convert::From::from(#value))
+        } else {
+            value
+        }
+    };
+    let cloned = prefill(&|field| {
+        let member = &field.member;
+        into_storage(field, quote!(::core::clone::Clone::clone(&self.#member)))
+    });
+    let moved = prefill(&|field| {
+        let member = &field.member;
+        into_storage(field, quote!(value.#member))
+    });
+
+    // Only to_builder() clones, and it must not stop structs with non-Clone
+    // fields from deriving a builder. A bound that names no generic
+    // parameter is checked where it is written, so each one is made
+    // higher-ranked to have it checked where to_builder() is called instead.
+    let clone_bounds = fields.iter().map(|field| {
+        let (ty, lifetimes) = higher_ranked_type(field.ty);
+        quote! {
+            for<'__builder, #(#lifetimes),*> #ty: ::core::clone::Clone
+        }
+    });
+
+    quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            /// Starts a builder with every field set to this value's.
+            #vis fn to_builder(&self) -> #builder_ty
+            where
+                #(#clone_bounds,)*
+            {
+                #cloned
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#struct_name #ty_generics> for #builder_ty #where_clause {
+            fn from(value: #struct_name #ty_generics) -> Self {
+                #moved
+            }
+        }
+    }
+}
+
+
+// `Clone` and `Debug` for a builder, so that a half-filled one can be kept as
+// a template or logged. As with to_builder(), the bound on each stored type
+// is higher-ranked, so that a field without the trait leaves the builder
+// without it instead of failing to compile. `generics` are the builder's
+// own, including the state parameters of a typestate builder, whose required
+// fields are stored as those parameters rather than as Options.
+fn generate_builder_traits(fields: &[BuilderField], builder_name: &syn::Ident, generics: &syn::Generics, typestate: bool, phantom: bool) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let predicates = where_clause.map(|where_clause| {
+        let predicates = where_clause.predicates.iter();
+        quote!(#(#predicates,)*)
+    });
+    let stored_state = |field: &BuilderField| typestate && field.is_required();
+    let bound = |field: &BuilderField, bound: proc_macro2::TokenStream| {
+        let stored: syn::Type = if stored_state(field) {
+            let param = typestate_param(&field.name);
+            syn::parse_quote!(#param)
+        } else {
+            field.storage_type().clone()
+        };
+        let (stored, lifetimes) = higher_ranked_type(&stored);
+        quote! {
+            for<'__builder, #(#lifetimes),*> #stored: #bound
+        }
+    };
+
+    let clone_bounds = fields.iter().map(|field| bound(field, quote!(::core::clone::Clone)));
+    let cloned = fields.iter().map(|field| {
+        let name = &field.name;
+        quote! { #name: ::core::clone::Clone::clone(&self.#name) }
+    });
+    let phantom_field = phantom.then(|| quote! {
+        __phantom: ::core::marker::PhantomData,
+    });
+
+    // Redacted fields are never printed, so they need no Debug. For a typestate
+    // builder that means a redacted required field's state can't be printed
+    // either, so there is one Debug impl for each combination of those fields
+    // being set or `__Unset`, with the state written out.
+    let debug_bounds: Vec<proc_macro2::TokenStream> = fields.iter().filter(|field| !field.options.redact).map(|field| bound(field, quote!(::core::fmt::Debug))).collect();
+    let redacted_states: Vec<&BuilderField> = fields.iter().filter(|field| stored_state(field) && field.options.redact).collect();
+    let builder_name_as_str = builder_name.to_string();
+    let debug_impls = (0..1usize << redacted_states.len()).map(|set| {
+        let state = |param: &syn::Ident| {
+            let index = redacted_states.iter().position(|field| typestate_param(&field.name) == *param)?;
+            Some((set & (1 << index) != 0, redacted_states[index].ty))
+        };
+        let mut debug_generics = generics.clone();
+        debug_generics.params = generics.params.iter().filter(|param| !matches!(param, syn::GenericParam::Type(ty) if state(&ty.ident).is_some())).cloned().collect();
+        let (debug_impl_generics, _, _) = debug_generics.split_for_impl();
+        let arguments = generics.params.iter().zip(generic_arguments(generics)).map(|(param, argument)| match param {
+            syn::GenericParam::Type(ty) => match state(&ty.ident) {
+                Some((true, field_ty)) => field_ty.to_token_stream(),
+                Some((false, _)) => quote!(::derive_builder::__Unset),
+                None => argument,
+            },
+            _ => argument,
+        });
+
+        let debug_fields = fields.iter().map(|field| {
+            let name = &field.name;
+            let name_as_str = name_as_str(name);
+            if stored_state(field) {
+                let value = match state(&typestate_param(name)) {
+                    Some((true, _)) => quote!(&::core::format_args!("<redacted>")),
+                    _ => quote!(&self.#name),
+                };
+                return quote! {
+                    debug.field(#name_as_str, #value);
+                };
+            }
+            let value = if field.options.redact {
+                quote!(&::core::format_args!("<redacted>"))
+            } else {
+                quote!(value)
+            };
+            quote! {
+                match &self.#name {
+                    ::core::option::Option::Some(value) => {
+                        debug.field(#name_as_str, #value);
+                    }
+                    ::core::option::Option::None => {
+                        debug.field(#name_as_str, &::core::format_args!("<unset>"));
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #[allow(non_camel_case_types)]
+            impl #debug_impl_generics ::core::fmt::Debug for #builder_name<#(#arguments),*>
+            where
+                #predicates
+                #(#debug_bounds,)*
+            {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    let mut debug = f.debug_struct(#builder_name_as_str);
+                    #(#debug_fields)*
+                    debug.finish()
+                }
+            }
+        }
+    });
+
+    quote! {
+        #[allow(non_camel_case_types)]
+        impl #impl_generics ::core::clone::Clone for #builder_name #ty_generics
+        where
+            #predicates
+            #(#clone_bounds,)*
+        {
+            fn clone(&self) -> Self {
+                #builder_name {
+                    #(#cloned,)*
+                    #phantom_field
+                }
+            }
+        }
+
+        #(#debug_impls)*
+    }
+}
+
+
+// Rewrites every named lifetime in `ty` other than 'static to a fresh one
+// and returns them, for use in a `for<...>` bound. Bounds on `&'a str` and
+// `&'b str` would otherwise both apply to any `&str` and make using either
+// one ambiguous.
+fn higher_ranked_type(ty: &syn::Type) -> (proc_macro2::TokenStream, Vec<syn::Lifetime>) {
+    fn rewrite(tokens: proc_macro2::TokenStream, lifetimes: &mut Vec<syn::Lifetime>) -> proc_macro2::TokenStream {
+        let mut after_apostrophe = false;
+        tokens.into_iter().map(|token| {
+            let token = match token {
+                proc_macro2::TokenTree::Ident(ident) if after_apostrophe && ident != "static" => {
+                    let fresh = format_ident!("__builder{}", lifetimes.len());
+                    lifetimes.push(syn::Lifetime { apostrophe: ident.span(), ident: fresh.clone() });
+                    proc_macro2::TokenTree::Ident(fresh)
+                }
+                proc_macro2::TokenTree::Group(group) => {
+                    let mut rewritten = proc_macro2::Group::new(group.delimiter(), rewrite(group.stream(), lifetimes));
+                    rewritten.set_span(group.span());
+                    proc_macro2::TokenTree::Group(rewritten)
+                }
+                other => other,
+            };
+            after_apostrophe = matches!(&token, proc_macro2::TokenTree::Punct(punct) if punct.as_char() == '\'');
+            token
+        }).collect()
+    }
+
+    let mut lifetimes = Vec::new();
+    let ty = rewrite(ty.to_token_stream(), &mut lifetimes);
+    (ty, lifetimes)
+}
+
+
+// With a struct-level `builder(default)`, unset fields are moved out of a
+// default instance of the struct that is created once per `build` call
+fn generate_struct_default(fields: &[BuilderField], options: &BuilderOptions, struct_name: &syn::Ident, generics: &syn::Generics) -> proc_macro2::TokenStream {
+    if !options.default || fields.iter().all(|field| field.options.default.is_some()) {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    quote! {
+        let __default: #struct_name #ty_generics = ::core::default::Default::default();
+    }
+}
+
+// The bound a struct-level `builder(default)` needs on generic structs
+fn struct_default_bound(options: &BuilderOptions, struct_name: &syn::Ident, generics: &syn::Generics) -> proc_macro2::TokenStream {
+    if !options.default || generics.type_params().next().is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    quote! {
+        #struct_name #ty_generics: ::core::default::Default,
+    }
+}
+
+
+// The `T` of an `Option<T>` or a `Vec<T>`, written either bare or as the
+// full path of the standard type, e.g. `core::option::Option<T>`. Aliases
+// can't be seen through, so they need `builder(optional)` instead.
+fn inner_type<'a>(ty: &'a syn::Type, wrapper: &str) -> Option<&'a syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    if type_path.qself.is_some() {
+        return None;
+    }
+
+    let segments: Vec<&syn::PathSegment> = type_path.path.segments.iter().collect();
+    let (segment, prefix) = segments.split_last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let crates: &[&str] = if wrapper == "Vec" { &["std", "alloc"] } else { &["std", "core"] };
+    let module = wrapper.to_lowercase();
+    let is_standard = match prefix {
+        [] => type_path.path.leading_colon.is_none(),
+        [krate, parent] => {
+            crates.iter().any(|name| krate.ident == name)
+                && parent.ident == module
+                && krate.arguments.is_none()
+                && parent.arguments.is_none()
+        }
+        _ => false,
+    };
+    if !is_standard {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(generic) if generic.args.len() == 1 => {
+            match &generic.args[0] {
+                syn::GenericArgument::Type(inner) => Some(inner),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+
+// The builder a `builder(sub_builder)` field's type derives by default:
+// `a::Inner<T>` is built by `a::InnerBuilder<T>`
+fn sub_builder_type(ty: &syn::Type) -> Option<syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    if type_path.qself.is_some() {
+        return None;
+    }
+
+    let mut builder = type_path.clone();
+    let segment = builder.path.segments.last_mut()?;
+    segment.ident = format_ident!("{}Builder", segment.ident);
+    Some(syn::Type::Path(builder))
+}
+
+
+// The element type of a collection, read off its tokens: the key and value
+// of a `...Map<K, V>`, the first type argument of other generic collections,
+// and the collection's IntoIterator::Item for anything else
+enum CollectionItem {
+    Single(proc_macro2::TokenStream),
+    Pair(proc_macro2::TokenStream, proc_macro2::TokenStream),
+}
+
+fn collection_item(ty: &syn::Type) -> Option<CollectionItem> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+
+    let arguments: Vec<&syn::Type> = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(generic) => generic.args.iter().filter_map(|argument| match argument {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }).collect(),
+        _ => Vec::new(),
+    };
+
+    if segment.ident.to_string().ends_with("Map") && arguments.len() >= 2 {
+        Some(CollectionItem::Pair(arguments[0].to_token_stream(), arguments[1].to_token_stream()))
+    } else if let Some(first) = arguments.first() {
+        Some(CollectionItem::Single(first.to_token_stream()))
+    } else {
+        Some(CollectionItem::Single(quote!(<#ty as ::core::iter::IntoIterator>::Item)))
+    }
+}
+
+
+// The `builder(constructor = "...")` entry points, which start a builder and
+// pass each of their arguments to the setter of the same name. `builder_ty`
+// is the type of builder a constructor returns.
+fn generate_constructors(target: &Target, options: &BuilderOptions, builder_ty: impl Fn(&Constructor) -> proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let (builder_fn, vis) = (&target.builder_fn, &target.vis);
+    let built = target.constructor().to_string().replace(' ', "");
+    options.constructors.iter().map(|constructor| {
+        let name = &constructor.name;
+        let (arguments, types): (Vec<&syn::Ident>, Vec<&syn::Type>) = constructor.arguments.iter().map(|(argument, ty)| (argument, ty)).unzip();
+        let listed: Vec<String> = arguments.iter().map(|argument| format!("`{}`", argument)).collect();
+        let doc = if listed.is_empty() {
+            format!(" Starts a builder for [`{}`].", built)
+        } else {
+            format!(" Starts a builder for [`{}`] with {} already set.", built, listed.join(", "))
+        };
+        let returns = builder_ty(constructor);
+
+        // Owned and typestate setters hand the builder back instead of
+        // changing it in place
+        let body = if options.typestate || options.pattern == Pattern::Owned {
+            quote! {
+                let builder = Self::#builder_fn();
+                #(let builder = builder.#arguments(#arguments);)*
+                builder
+            }
+        } else {
+            quote! {
+                #[allow(unused_mut)]
+                let mut builder = Self::#builder_fn();
+                #(builder.#arguments(#arguments);)*
+                builder
+            }
+        };
+
+        quote! {
+            #[doc = #doc]
+            #vis fn #name(#(#arguments: #types),*) -> #returns {
+                #body
+            }
+        }
+    }).collect()
+}
+
+
+// What a field is called in errors, `unset_fields()` and Debug output: its
+// name, without the `r#` of a raw identifier
+fn name_as_str(ident: &syn::Ident) -> String {
+    syn::ext::IdentExt::unraw(ident).to_string()
+}
+
+// `CamelCase` to `camel_case`, for naming things after enum variants
+fn snake_case(ident: &syn::Ident) -> String {
+    let mut snake = String::new();
+    for (i, c) in name_as_str(ident).chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+// In typestate mode each required field gets its own type parameter on the
+// builder. It is `::derive_builder::__Unset` until the setter is called and
+// the field's type after, so `build` only exists once every required field
+// has been given a value.
+fn typestate_param(field_name: &syn::Ident) -> syn::Ident {
+    format_ident!("__{}", field_name)
+}
+
+// The struct's own generic parameters as they are written in argument
+// position, e.g. `'a, T, N` for `<'a, T: Clone, const N: usize>`
+fn generic_arguments(generics: &syn::Generics) -> Vec<proc_macro2::TokenStream> {
+    generics.params.iter().map(|param| match param {
+        syn::GenericParam::Lifetime(lifetime) => lifetime.lifetime.to_token_stream(),
+        syn::GenericParam::Type(ty) => ty.ident.to_token_stream(),
+        syn::GenericParam::Const(constant) => constant.ident.to_token_stream(),
+    }).collect()
+}
+
+// Whether any of the struct's type parameters appear in the tokens of `ty`
+fn mentions_type_param(ty: &impl ToTokens, generics: &syn::Generics) -> bool {
+    fn visit(tokens: proc_macro2::TokenStream, params: &[&syn::Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) => params.contains(&&ident),
+            proc_macro2::TokenTree::Group(group) => visit(group.stream(), params),
+            _ => false,
+        })
+    }
+
+    let params: Vec<&syn::Ident> = generics.type_params().map(|param| &param.ident).collect();
+    visit(ty.to_token_stream(), &params)
+}
+
+fn gen_typestate_builder(fields: &[BuilderField], skipped: &[BuilderField], target: &Target, options: &BuilderOptions) -> proc_macro2::TokenStream {
+    let (struct_name_ident, generics, builder_name, builder_fn, vis) = (target.ident, target.generics, &target.builder_name, &target.builder_fn, &target.vis);
+    let required: Vec<&BuilderField> = fields.iter().filter(|field| field.is_required()).collect();
+    let params: Vec<syn::Ident> = required.iter().map(|field| typestate_param(&field.name)).collect();
+    let required_types: Vec<&syn::Type> = required.iter().map(|field| field.ty).collect();
+
+    // The builder is generic over the struct's parameters followed by one
+    // state parameter per required field
+    let struct_arguments = generic_arguments(generics);
+    let mut builder_generics = generics.clone();
+    builder_generics.params.extend(params.iter().map(|param| -> syn::GenericParam { syn::parse_quote!(#param = ::derive_builder::__Unset) }));
+    let mut state_generics = generics.clone();
+    state_generics.params.extend(params.iter().map(|param| -> syn::GenericParam { syn::parse_quote!(#param) }));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (state_impl_generics, state_ty_generics, _) = state_generics.split_for_impl();
+
+    let builder_fields = fields.iter().map(|field| {
+        let name = &field.name;
+        if field.is_required() {
+            let param = typestate_param(name);
+            quote! { #name: #param }
+        } else {
+            let ty = field.storage_type();
+            quote! { #name: ::core::option::Option<#ty> }
+        }
+    });
+
+    let initial_values = fields.iter().map(|field| {
+        let name = &field.name;
+        if field.is_required() {
+            quote! { #name: ::derive_builder::__Unset }
+        } else {
+            quote! { #name: ::core::option::Option::None }
+        }
+    });
+
+    let setters = fields.iter().map(|field| {
+        let (name, vis) = (&field.name, &field.vis);
+        let docs = field.docs(format!(" Sets `{}`.", name));
+        let ty = field.ty;
+
+        // Whether a required field is set is part of the builder's type, so
+        // only the others can be looked at
+        if !field.is_required() {
+            let setters = generate_field_setters(field, quote!(mut self), quote!(Self));
+            let mutator = generate_field_mutator(field, quote!(mut self), quote!(Self));
+            let introspection = generate_field_introspection(field, quote!(mut self), quote!(Self));
+            return quote! {
+                #setters
+                #mutator
+                #introspection
+            };
+        }
+
+        // Moving to the next state means rebuilding the builder with this
+        // field's parameter swapped for its real type
+        let param = typestate_param(name);
+        let next_state = params.iter().map(|other| {
+            if *other == param {
+                quote! { #ty }
+            } else {
+                quote! { #other }
+            }
+        });
+        let next_builder = quote! {
+            #builder_name<#(#struct_arguments,)* #(#next_state),*>
+        };
+        let carried: Vec<proc_macro2::TokenStream> = fields.iter().filter(|other| other.name != *name).map(|other| {
+            let other_name = &other.name;
+            quote! { #other_name: self.#other_name }
+        }).collect();
+        let (argument_ty, value) = setter_argument(field, ty, &format_ident!("argument"));
+
+        let try_setter = if field.options.setter.try_into.unwrap_or(false) {
+            let try_name = format_ident!("try_{}", name);
+            quote! {
+                #docs
+                #vis fn #try_name<__Value: ::core::convert::TryInto<#ty>>(self, argument: __Value) -> ::core::result::Result<#next_builder, <__Value as ::core::convert::TryInto<#ty>>::Error> {
+                    ::core::result::Result::Ok(#builder_name {
+                        #name: ::core::convert::TryInto::try_into(argument)?,
+                        #(#carried,)*
+                        __phantom: ::core::marker::PhantomData,
+                    })
+                }
+            }
+        } else {
+            proc_macro2::TokenStream::new()
+        };
+
+        quote! {
+            #docs
+            #vis fn #name(self, argument: #argument_ty) -> #next_builder {
+                #builder_name {
+                    #name: #value,
+                    #(#carried,)*
+                    __phantom: ::core::marker::PhantomData,
+                }
+            }
+
+            #try_setter
+        }
+    });
+
+    let error_type = options.error_type();
+    let struct_default = generate_struct_default(fields, options, struct_name_ident, generics);
+    let struct_default_bound = struct_default_bound(options, struct_name_ident, generics);
+    let validation_hooks = generate_validation_hooks(fields, options, true);
+    let complete_builder = quote! {
+        #builder_name<#(#struct_arguments,)* #(#required_types),*>
+    };
+    let conversions = if target.variant.is_none() {
+        generate_conversions_to_builder(fields, target, &complete_builder, true, true)
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+    let moved_fields = fields.iter().map(|field| {
+        let (name, member) = (&field.name, &field.member);
+        if field.is_required() {
+            quote! { #member: self.#name }
+        } else {
+            let resolved = resolve_field(field, quote!(self.#name));
+            quote! { #member: #resolved }
+        }
+    });
+
+    let skipped_fields = generate_skipped_fields(skipped);
+    let (builder_docs, build_docs) = generate_build_docs(fields, target);
+    let builder_struct_doc = target.builder_struct_doc();
+    let constructor = target.constructor();
+    let finish = finish_build(quote! {
+        #constructor {
+            #(#moved_fields,)*
+            #skipped_fields
+        }
+    }, skipped, options.post_build.as_ref());
+    let build_fn = &target.build_fn;
+    let traits = generate_builder_traits(fields, builder_name, &state_generics, true, true);
+
+    // A constructor's builder has the states of the required fields it sets
+    let constructors = generate_constructors(target, options, |constructor| {
+        let states = required.iter().map(|field| {
+            if constructor.arguments.iter().any(|(argument, _)| *argument == field.name) {
+                field.ty.to_token_stream()
+            } else {
+                quote!(::derive_builder::__Unset)
+            }
+        });
+        quote! {
+            #builder_name<#(#struct_arguments,)* #(#states),*>
+        }
+    });
+
+    quote! {
+        impl #impl_generics #struct_name_ident #ty_generics #where_clause {
+            #builder_docs
+            #vis fn #builder_fn() -> #builder_name #ty_generics {
+                #builder_name {
+                    #(#initial_values,)*
+                    __phantom: ::core::marker::PhantomData,
+                }
+            }
+
+            #constructors
+        }
+
+        #[allow(non_camel_case_types)]
+        #[doc = #builder_struct_doc]
+        #vis struct #builder_name #builder_generics #where_clause {
+            #(#builder_fields,)*
+            // Required fields no longer mention the struct's parameters once
+            // they are replaced by state parameters, so keep them used here
+            __phantom: ::core::marker::PhantomData<fn() -> #struct_name_ident #ty_generics>,
+        }
+
+        #[allow(non_camel_case_types)]
+        impl #state_impl_generics #builder_name #state_ty_generics #where_clause {
+            #(#setters)*
+        }
+
+        #traits
+
+        #conversions
+
+        impl #impl_generics #complete_builder #where_clause {
+            #build_docs
+            #vis fn #build_fn(self) -> ::core::result::Result<#struct_name_ident #ty_generics, #error_type>
+            where
+                #struct_default_bound
+            {
+                #validation_hooks
+                #struct_default
+                #finish
+            }
+        }
+    }
+}
+
+
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive(input: TokenStream) -> TokenStream {
+
+    pub fn gen_builder_str_for_struct(fields: &[BuilderField], skipped: &[BuilderField], target: &Target, options: &BuilderOptions) -> proc_macro2::TokenStream {
+        let (struct_name_ident, generics, builder_name, builder_fn, vis) = (target.ident, target.generics, &target.builder_name, &target.builder_fn, &target.vis);
+        let builder_fields = generate_member_variables_of_builder(fields);
+        let setters = generate_setter_functions_of_builder(fields, options.pattern, vis);
+        let validators = generate_validators(fields);
+        let validation_hooks = generate_validation_hooks(fields, options, false);
+        let final_constructor = generate_setters_for_final_constructor(fields, skipped, target.constructor(), options.pattern);
+        let finish = finish_build(final_constructor, skipped, options.post_build.as_ref());
+        let error_type = options.error_type();
+        let struct_default = generate_struct_default(fields, options, struct_name_ident, generics);
+        let struct_default_bound = struct_default_bound(options, struct_name_ident, generics);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        // Skipped fields may be the only ones that use some of the struct's
+        // parameters, and a variant need not use all of its enum's
+        let phantom = !skipped.is_empty() || target.variant.is_some();
+        let phantom_field = phantom.then(|| quote! {
+            __phantom: ::core::marker::PhantomData<fn() -> #struct_name_ident #ty_generics>,
+        });
+        let default_constructor = generate_default_setters_for_builder(fields, builder_name, phantom);
+        let conversions = if target.variant.is_none() {
+            generate_conversions_to_builder(fields, target, &quote!(#builder_name #ty_generics), false, phantom)
+        } else {
+            proc_macro2::TokenStream::new()
+        };
+        let from_env = generate_from_env(fields, options, target);
+        let (builder_docs, build_docs) = generate_build_docs(fields, target);
+        let builder_struct_doc = target.builder_struct_doc();
+        let build_fn = &target.build_fn;
+        let constructors = generate_constructors(target, options, |_| quote!(#builder_name #ty_generics));
+        let traits = generate_builder_traits(fields, builder_name, generics, false, phantom);
+        let (constness, build_const) = if options.const_fn {
+            (quote!(const), generate_build_const(fields, skipped, target))
+        } else {
+            (proc_macro2::TokenStream::new(), proc_macro2::TokenStream::new())
+        };
+        // The immutable build() clones every field, which only needs
+        // spelling out for types that depend on the struct's type parameters
+        let field_types: Vec<&syn::Type> = fields.iter().map(BuilderField::storage_type).filter(|ty| options.pattern == Pattern::Immutable && mentions_type_param(ty, generics)).collect();
+        let build_receiver = match options.pattern {
+            Pattern::Immutable => quote!(&self),
+            Pattern::Mutable => quote!(&mut self),
+            Pattern::Owned => quote!(self),
+        };
+        // An outer builder that holds this one as a `builder(sub_builder)`
+        // can't know what `build_fn` renamed `build` to, so it calls this
+        let sub_build = target.variant.is_none().then(|| quote! {
+            #[doc(hidden)]
+            #vis fn __build(#build_receiver) -> ::core::result::Result<#struct_name_ident #ty_generics, #error_type>
+            where
+                #(#field_types: ::core::clone::Clone,)*
+                #struct_default_bound
+            {
+                self.#build_fn()
+            }
+        });
+
+        quote! {
+            impl #impl_generics #struct_name_ident #ty_generics #where_clause {
+                #builder_docs
+                #vis #constness fn #builder_fn() -> #builder_name #ty_generics {
+                    let build = #default_constructor;
+                    return build;
+                }
+
+                #constructors
+            }
+
+            #[doc = #builder_struct_doc]
+            #vis struct #builder_name #generics #where_clause {
+                #builder_fields
+                #phantom_field
+            }
+
+            #traits
+
+            #conversions
+
+            #from_env
+
+            impl #impl_generics #builder_name #ty_generics #where_clause {
+                #setters
+
+                #build_docs
+                #vis fn #build_fn(#build_receiver) -> ::core::result::Result<#struct_name_ident #ty_generics, #error_type>
+                where
+                    #(#field_types: ::core::clone::Clone,)*
+                    #struct_default_bound
+                {
+                    #validators
+                    #validation_hooks
+                    #struct_default
+                    #finish
+                }
+
+                #sub_build
+
+                #build_const
+            }
+        }
+    }
+
+    let DeriveInput {
+        ident: struct_name_ident,
+        vis,
+        attrs,
+        generics,
+        data,
+        ..
+    } = parse_macro_input!(input as DeriveInput);
+
+    // Attribute errors don't stop the expansion: the builder is still
+    // generated from whatever did parse, so that the rest of the caller's
+    // code keeps type checking while the errors are being fixed
+    let mut errors = Errors::default();
+    let options = parse_struct_attributes(&attrs, &mut errors);
+    let vis = options.vis.clone().unwrap_or(vis);
+
+    let builder = match &data {
+        Struct(my_struct) => {
+            let target = Target {
+                ident: &struct_name_ident,
+                generics: &generics,
+                vis: vis.clone(),
+                variant: None,
+                builder_name: options.builder_name(&struct_name_ident),
+                builder_fn: format_ident!("builder"),
+                build_fn: options.build_fn_name(),
+            };
+            let (skipped, fields): (Vec<BuilderField>, Vec<BuilderField>) = collect_fields(&my_struct.fields, &options, &vis, &mut errors).into_iter().partition(BuilderField::is_skipped);
+
+            // A constructor can only set what the builder has a setter for,
+            // which `each` fields don't, and sub-builders are filled in place
+            let mut options = options.clone();
+            options.constructors.retain(|constructor| {
+                let mut valid = true;
+                for (argument, _) in &constructor.arguments {
+                    let message = match fields.iter().find(|field| field.name == *argument) {
+                        None => format!("the builder has no field `{}` to set", argument),
+                        Some(field) if field.options.each.is_some() => format!("`{}` is set one item at a time, so it cannot be a constructor argument", argument),
+                        Some(field) if field.options.sub_builder.is_some() => format!("`{}` has a sub-builder, so it cannot be a constructor argument", argument),
+                        Some(_) => continue,
+                    };
+                    errors.push(syn::Error::new(argument.span(), message));
+                    valid = false;
+                }
+                valid
+            });
+
+            let builder = if options.typestate {
+                gen_typestate_builder(&fields, &skipped, &target, &options)
+            } else {
+                gen_builder_str_for_struct(&fields, &skipped, &target, &options)
+            };
+            let partial = if options.partial {
+                generate_partial(&fields, &target, !skipped.is_empty())
+            } else {
+                proc_macro2::TokenStream::new()
+            };
+            quote! {
+                #builder
+                #partial
+            }
+        }
+        Enum(my_enum) => {
+            // Every variant gets a builder of its own, so there is no one
+            // name or default value for the enum as a whole
+            if let Some(name) = &options.name {
+                errors.push(syn::Error::new(name.span(), "enums get one builder per variant, named `<Enum><Variant>Builder`"));
+            }
+            if options.default {
+                errors.push(syn::Error::new_spanned(my_enum.enum_token, "`builder(default)` cannot be used on enums"));
+            }
+            if options.partial {
+                errors.push(syn::Error::new_spanned(my_enum.enum_token, "`builder(partial)` cannot be used on enums"));
+            }
+            if let Some(constructor) = options.constructors.first() {
+                errors.push(syn::Error::new(constructor.name.span(), "`builder(constructor = \"...\")` cannot be used on enums"));
+            }
+
+            let builders = my_enum.variants.iter().filter(|variant| matches!(variant.fields, syn::Fields::Named(_))).map(|variant| {
+                let target = Target {
+                    ident: &struct_name_ident,
+                    generics: &generics,
+                    vis: vis.clone(),
+                    variant: Some(&variant.ident),
+                    builder_name: format_ident!("{}{}Builder", struct_name_ident, variant.ident),
+                    builder_fn: format_ident!("{}_builder", snake_case(&variant.ident)),
+                    build_fn: options.build_fn_name(),
+                };
+                let mut options = options.clone();
+                options.default = false;
+                options.constructors.clear();
+                let (skipped, fields): (Vec<BuilderField>, Vec<BuilderField>) = collect_fields(&variant.fields, &options, &vis, &mut errors).into_iter().partition(BuilderField::is_skipped);
+                for field in &skipped {
+                    if field.options.compute.is_some() {
+                        errors.push(syn::Error::new_spanned(field.ty, "`builder(compute = \"...\")` cannot be used on enum variants"));
+                    }
+                }
+
+                if options.typestate {
+                    gen_typestate_builder(&fields, &skipped, &target, &options)
+                } else {
+                    gen_builder_str_for_struct(&fields, &skipped, &target, &options)
+                }
+            });
+            quote! {
+                #(#builders)*
+            }
+        }
+        Union(my_union) => {
+            errors.push(syn::Error::new_spanned(my_union.union_token, "derive(Builder) does not support unions"));
+            proc_macro2::TokenStream::new()
+        }
+    };
+    let errors = errors.to_compile_error();
+
+    quote! {
+        #builder
+        #errors
+    }.into()
+}
@@ -1,319 +1,245 @@
-extern crate proc_macro2;
-
-use std::fmt::format;
-use std::str::from_utf8;
-
-use proc_macro::TokenStream;
-use proc_macro2::Span;
-use syn::{parse_macro_input, Data::Struct, DataStruct, DeriveInput};
-use quote::quote;
-use quote::ToTokens;
+// Crates that have the "proc-macro" crate type are only allowed to export
+// procedural macros, but the builders generated by derive(Builder) need an
+// error type that is shared between every derived struct. The derive lives in
+// the derive_builder_impl crate and is re-exported from here, alongside the
+// runtime types the generated code refers to.
+//
+// Everything but reading the process environment only needs `alloc`, so the
+// crate is no_std unless its default `std` feature is enabled.
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub use derive_builder_impl::Builder;
+
+// Generated code reaches `String`, `Vec` and `vec!` through here, since
+// `::alloc` only resolves in crates that declare `extern crate alloc`
+#[doc(hidden)]
+pub extern crate alloc as __alloc;
+
+// Expands to the generated `from_env`, which reads the process environment,
+// only when that is available
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_std {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
 
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_std {
+    ($($item:tt)*) => {};
+}
 
-fn create_macros() -> proc_macro2::TokenStream {
-    quote!{
-        // This macro creates a setter for our builder.
-        // e.g. if our builder wants a type of Option
-        // we use this macro to accept the T from Option<T>
-        macro_rules! create_builder_setter {
-            ($fname: ident, Option<$ftype: ty>) => {
-                pub fn $fname(&mut self, argument: $ftype) -> &mut Self {
-                    self.$fname = Some(Some(argument));
-                    self
-                }
-            };
-            ($fname: ident, $ftype: ty) => {
-                pub fn $fname(&mut self, argument: $ftype) -> &mut Self {
-                    self.$fname = Some(argument);
-                    self
-                }
-            };
-        }
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt::{self, Display};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+// The state of a required field of a typestate builder until its setter is
+// called. It can't be the type of a real field, unlike `()`, and shows up as
+// `<unset>` in the builder's Debug output.
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+pub struct __Unset;
+
+impl fmt::Debug for __Unset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("<unset>")
+    }
+}
 
-        macro_rules! create_builder_each_setter {
-            ($fname: ident, $argname: ident, Vec<$ftype: ty>) => {
-                pub fn $fname(&mut self, argument: $ftype) -> &mut Self {
-                    if self.$argname.is_none() {
-                        self.$argname = Some(Vec::new());
-                    } 
+/// Error returned by a generated builder's `build` method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuilderError {
+    /// `build` was called before these required fields were set. Every
+    /// missing field is listed, in declaration order.
+    MissingFields(Vec<String>),
+    /// A `builder(validate = "...")` hook rejected the builder's values.
+    /// `field` names the field whose check failed, and is `None` for the
+    /// struct-level check.
+    Validation {
+        field: Option<String>,
+        message: String,
+    },
+}
 
-                    // We assigned this just above
-                    self.$argname.as_mut().unwrap().push(argument);
-                    self
-                }
-            };
+impl BuilderError {
+    /// Moves an error from building the `builder(sub_builder)` of `field` to
+    /// the builder that contains it, by prefixing every field name it
+    /// reports with `field.`
+    pub fn in_field(self, field: &str) -> BuilderError {
+        match self {
+            BuilderError::MissingFields(fields) => BuilderError::MissingFields(
+                fields.into_iter().map(|inner| format!("{}.{}", field, inner)).collect(),
+            ),
+            BuilderError::Validation { field: inner, message } => BuilderError::Validation {
+                field: Some(match inner {
+                    Some(inner) => format!("{}.{}", field, inner),
+                    None => field.to_owned(),
+                }),
+                message,
+            },
         }
+    }
+}
 
-        macro_rules! create_final_builder_unwrap {
-            ($fname: ident, Option<$ftype: ty>) => {
-                pub fn $fname(argument: &std::option::Option<std::option::Option<$ftype>>) -> std::option::Option<$ftype> {
-                    match argument {
-                        Some(arg) => {
-                            return arg.clone();
-                        }
-                        None => {
-                            return None;
-                        }
-                    }
+impl Display for BuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuilderError::MissingFields(fields) => {
+                write!(f, "missing required field")?;
+                if fields.len() > 1 {
+                    write!(f, "s")?;
                 }
-            };
-            ($fname: ident, Vec<$ftype: ty>) => {
-                pub fn $fname(argument: &std::option::Option<Vec<$ftype>>) -> Vec<$ftype> {
-                    match argument {
-                        Some(arg) => {
-                            return arg.clone();
-                        }
-                        None => {
-                            return Vec::new();
-                        }
-                    }
-                }
-            };
-            ($fname: ident, $ftype: ty) => {
-                pub fn $fname(argument: &std::option::Option<$ftype>) -> $ftype {
-                    argument.as_ref().unwrap().clone()
-                }
-            };
-        }
-
-        // We use this macro to check if a non-optional
-        // value is set and just return None otherwise
-        macro_rules! validate_field_on_build {
-            ($self: ident, $fname: ident, Option<$ftype: ty>) => {
-            };
-            ($self: ident, $fname: ident, Vec<$ftype: ty>) => {
-            };
-            ($self: ident, $fname: ident, $ftype: ty) => {
-                // Non optional is set to None... whaaat
-                if $self.$fname.is_none() {
-                    panic!{"Non-optional param is set to none {}", stringify!{$fname}}
+                for (i, field) in fields.iter().enumerate() {
+                    let separator = if i == 0 { ": " } else { ", " };
+                    write!(f, "{}`{}`", separator, field)?;
                 }
-            };
+                Ok(())
+            }
+            BuilderError::Validation { field: Some(field), message } => {
+                write!(f, "invalid `{}`: {}", field, message)
+            }
+            BuilderError::Validation { field: None, message } => {
+                write!(f, "validation failed: {}", message)
+            }
         }
-
-
     }
 }
 
-
-fn generate_member_variables_of_builder(my_struct: &DataStruct) -> proc_macro2::TokenStream {
-    let fields = my_struct.fields.iter().map(|field| {
-        let name = &field.ident; 
-        let ty = &field.ty;
-
-        quote! {
-            #name: std::option::Option<#ty>
-        }
-    });
-
-    quote! {
-        #(#fields,)*
-    }.into()
+impl Error for BuilderError {}
+
+/// Error returned by a generated builder's `from_env` and `from_env_with`.
+/// Each variant names the environment variable that could not be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EnvError {
+    /// The variable is set, but its value is not valid unicode.
+    NotUnicode { variable: String },
+    /// The variable's value, or one of the items of a list, was rejected by
+    /// the field type's `FromStr` impl.
+    Parse {
+        variable: String,
+        value: String,
+        message: String,
+    },
 }
 
-
-fn generate_setter_functions_of_builder(my_struct: &DataStruct) -> proc_macro2::TokenStream {
-    let setters = my_struct.fields.iter().map(|field| {
-        let name = &field.ident;
-
-        let name_as_str = match name {
-            Some(thang) => {
-                thang.to_string()
-            },  
-            None => {
-                "".to_owned()
+impl Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EnvError::NotUnicode { variable } => {
+                write!(f, "environment variable `{}` is not valid unicode", variable)
             }
-        };
-        let setter_name: proc_macro2::TokenStream = format!("{}_setter", name_as_str).parse().unwrap();
-
-        let ty = &field.ty;
-
-        let each_setter: proc_macro2::TokenStream = match parse_each_attribute(&field.attrs) {
-            Ok(optional_str) => {
-                match optional_str {
-                    Some(string) => {
-                        // string looks like : ""each"" - cut off the ""
-                        let cut_str: String = match from_utf8(&string.as_bytes()[1..string.len() - 1]) {
-                            Ok(s) => {
-                                s.to_owned()
-                            }
-                            Err(e) => {
-                                panic!("{}", e);
-                            }
-                        };
-                        
-                        let each_name: syn::Ident = syn::Ident::new(&cut_str, Span::call_site());
-                        quote! {
-                            create_builder_each_setter! (#each_name, #name, #ty);
-                        }
-                    },
-                    None => {
-                        quote! {
-                            create_builder_setter! (#name, #ty);
-                        }
-                    }
-                }
-            },
-            Err(error) => {
-                panic!("Error parsing attributes: {}", error);
+            EnvError::Parse { variable, value, message } => {
+                write!(f, "invalid value {:?} for environment variable `{}`: {}", value, variable, message)
             }
-        };
-
-        quote! {
-            create_final_builder_unwrap! (#setter_name, #ty);
-
-            #each_setter
         }
-    });
-
-    quote! {
-        #(#setters)*
-    }.into()
+    }
 }
 
+impl Error for EnvError {}
 
-fn generate_default_setters_for_builder(my_struct: &DataStruct) -> proc_macro2::TokenStream {
-    let default_setters = my_struct.fields.iter().map(|field| {
-        let name = &field.ident;
-        quote! {
-            #name: None
-        }
-    });
-
-    quote! {
-        Builder{#(#default_setters,)*}
-    }.into()
+/// Where `from_env_with` looks up variables. Any
+/// `Fn(&str) -> Option<String>` closure and string maps can be used, so
+/// that tests don't need to touch the process environment.
+pub trait EnvSource {
+    /// The value of the variable `name`, or `None` if it is not set.
+    fn var(&self, name: &str) -> Result<Option<String>, EnvError>;
 }
 
-
-fn generate_setters_for_final_constructor(my_struct: &DataStruct, struct_name: &syn::Ident) -> proc_macro2::TokenStream {
-    let builder = my_struct.fields.iter().map(|field| {
-        let name = &field.ident;
-        let name_as_str = match name {
-            Some(thang) => {
-                thang.to_string()
-            },  
-            None => {
-                "".to_owned()
-            }
-        };
-        let setter_name: proc_macro2::TokenStream = format!("{}_setter", name_as_str).parse().unwrap();
-
-        quote! {
-            #name: Self::#setter_name(&self.#name)
+/// The environment of the current process, used by `from_env`. Only
+/// available with the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessEnv;
+
+#[cfg(feature = "std")]
+impl EnvSource for ProcessEnv {
+    fn var(&self, name: &str) -> Result<Option<String>, EnvError> {
+        match std::env::var(name) {
+            Ok(value) => Ok(Some(value)),
+            Err(std::env::VarError::NotPresent) => Ok(None),
+            Err(std::env::VarError::NotUnicode(_)) => Err(EnvError::NotUnicode { variable: name.to_owned() }),
         }
-    });
-
-    quote! {
-        #struct_name{#(#builder,)*}
     }
 }
 
-fn generate_validators(my_struct: &DataStruct) -> proc_macro2::TokenStream {
-    let validators = my_struct.fields.iter().map(|field| {
-        let name = &field.ident;
-        let ty = &field.ty;
-
-        quote! {
-            validate_field_on_build! (self, #name, #ty);
-        }
-    });
-
-    quote! {
-        #(#validators)*
-    }.into()
+impl<F> EnvSource for F
+where
+    F: Fn(&str) -> Option<String>,
+{
+    fn var(&self, name: &str) -> Result<Option<String>, EnvError> {
+        Ok(self(name))
+    }
 }
 
-
-// Looks for each attriute (our attribute)
-fn parse_each_attribute(attr_vec: &Vec<syn::Attribute>) -> Result<Option<String>, String> {
-    if attr_vec.len() == 0 {
-        return Ok(None);
+#[cfg(feature = "std")]
+impl<K, V> EnvSource for HashMap<K, V>
+where
+    K: core::borrow::Borrow<str> + core::hash::Hash + Eq,
+    V: AsRef<str>,
+{
+    fn var(&self, name: &str) -> Result<Option<String>, EnvError> {
+        Ok(self.get(name).map(|value| value.as_ref().to_owned()))
     }
+}
 
-    let attr = &attr_vec[0];
-
-    if attr.path().is_ident("builder") {
-        match attr.parse_args() {
-            Ok(x) => {
-                match x {
-                    syn::Meta::NameValue(nv) => {
-                        match nv.path.get_ident() {
-                            Some(valid_ident) => {
-                                if *valid_ident == proc_macro2::Ident::new("each", Span::call_site()) {
-                                    return Ok(Some(nv.value.to_token_stream().to_string()));
-                                }
-                                else {
-                                    panic!("expected `builder(each = \"...\")`");
-                                }
-                            }
-                            None => {
-                                return Err(format(format_args!("Param ident cannot be None")));
-                            }
-                        }
-                    }
-                    _ => {
-                        panic!("Unexpected");
-                    }
-                }
-            }
-            Err(e) => {
-                panic!("{}", e);
-            }
-        }
+impl<K, V> EnvSource for BTreeMap<K, V>
+where
+    K: core::borrow::Borrow<str> + Ord,
+    V: AsRef<str>,
+{
+    fn var(&self, name: &str) -> Result<Option<String>, EnvError> {
+        Ok(self.get(name).map(|value| value.as_ref().to_owned()))
     }
-
-    return Ok(None);
 }
 
-
-#[proc_macro_derive(Builder, attributes(builder))]
-pub fn derive(input: TokenStream) -> TokenStream {
-
-    pub fn gen_builder_str_for_struct(my_struct: DataStruct, struct_name_ident: syn::Ident) -> TokenStream {
-        let macros: proc_macro2::TokenStream = create_macros();
-
-        let default_constructor = generate_default_setters_for_builder(&my_struct);
-        let fields = generate_member_variables_of_builder(&my_struct);
-        let setters = generate_setter_functions_of_builder(&my_struct);
-        let validators = generate_validators(&my_struct);
-        let final_constructor = generate_setters_for_final_constructor(&my_struct, &struct_name_ident);
-
-        quote! {
-            #macros
-
-            impl #struct_name_ident {
-                pub fn builder() -> Builder { 
-                    let build = #default_constructor; 
-                    return build; 
-                }
-            }
-
-            pub struct Builder {
-                #fields
-            }
-
-            impl Builder {
-                #setters
-
-                pub fn build(&self) -> std::option::Option<#struct_name_ident> {
-                    #validators
-                    return std::option::Option::Some(#final_constructor);
-                }
-            }
-        }.into()
+#[cfg(feature = "std")]
+impl<K, V> EnvSource for &HashMap<K, V>
+where
+    K: core::borrow::Borrow<str> + core::hash::Hash + Eq,
+    V: AsRef<str>,
+{
+    fn var(&self, name: &str) -> Result<Option<String>, EnvError> {
+        (**self).var(name)
     }
+}
 
-    let DeriveInput {
-        ident: struct_name_ident,
-        data,
-        ..
-    } = parse_macro_input!(input as DeriveInput);
-
-    let description_str = match data {
-        Struct(my_struct) => gen_builder_str_for_struct(my_struct, struct_name_ident),
-        _ => TokenStream::new(),
-    };
+impl<K, V> EnvSource for &BTreeMap<K, V>
+where
+    K: core::borrow::Borrow<str> + Ord,
+    V: AsRef<str>,
+{
+    fn var(&self, name: &str) -> Result<Option<String>, EnvError> {
+        (**self).var(name)
+    }
+}
 
-    return description_str;
+/// Parses the `value` of the environment variable `variable`, or one item of
+/// it, with `FromStr`.
+pub fn parse_env_var<T>(variable: &str, value: &str) -> Result<T, EnvError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.parse().map_err(|error: T::Err| EnvError::Parse {
+        variable: variable.to_owned(),
+        value: value.to_owned(),
+        message: error.to_string(),
+    })
 }
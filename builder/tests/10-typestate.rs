@@ -0,0 +1,40 @@
+// With #[builder(typestate)] the builder tracks which required fields have
+// been set in its type, so forgetting one is caught by the compiler instead
+// of at runtime. Optional and repeated fields can be set in any order, or not
+// at all.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .executable("cargo".to_owned())
+        .arg("--release".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert!(command.env.is_empty());
+    assert!(command.current_dir.is_none());
+
+    let command = Command::builder()
+        .current_dir("..".to_owned())
+        .env(vec!["RUST_LOG=debug".to_owned()])
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.env, vec!["RUST_LOG=debug"]);
+}
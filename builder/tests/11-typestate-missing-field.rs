@@ -0,0 +1,16 @@
+// In typestate mode, calling build() before every required field has been set
+// is a type error: there is no `build` method on a builder whose state
+// still has an unset field in it.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let _ = Command::builder().current_dir("..".to_owned()).build();
+}
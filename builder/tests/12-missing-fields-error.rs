@@ -0,0 +1,49 @@
+// Calling build() before every required field is set does not panic. It
+// returns a derive_builder::BuilderError that names all of the missing fields
+// at once, so the caller can report them together. A field with a raw
+// identifier for a name is reported without the `r#`.
+
+use derive_builder::{Builder, BuilderError};
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: String,
+    timeout: Option<u64>,
+}
+
+#[derive(Builder)]
+pub struct Token {
+    r#type: String,
+    value: String,
+}
+
+fn main() {
+    let error = Command::builder().arg("build".to_owned()).build().err().unwrap();
+    assert_eq!(
+        error,
+        BuilderError::MissingFields(vec!["executable".to_owned(), "current_dir".to_owned()]),
+    );
+    assert_eq!(
+        error.to_string(),
+        "missing required fields: `executable`, `current_dir`",
+    );
+
+    let error = Command::builder().executable("cargo".to_owned()).build().err().unwrap();
+    assert_eq!(error.to_string(), "missing required field: `current_dir`");
+
+    let boxed: Box<dyn std::error::Error> = Box::new(error);
+    assert!(boxed.source().is_none());
+
+    let mut token = Token::builder();
+    token.value("if".to_owned());
+    assert_eq!(token.unset_fields(), ["type"]);
+    assert_eq!(format!("{:?}", token), "TokenBuilder { type: <unset>, value: \"if\" }");
+    let error = token.build().err().unwrap();
+    assert_eq!(error, BuilderError::MissingFields(vec!["type".to_owned()]));
+    let token = token.r#type("keyword".to_owned()).build().unwrap();
+    assert_eq!(token.r#type, "keyword");
+}
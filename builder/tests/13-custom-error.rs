@@ -0,0 +1,42 @@
+// #[builder(error = "...")] makes build() return the caller's own error type.
+// The only requirement on it is a From<BuilderError> conversion.
+
+use derive_builder::{Builder, BuilderError};
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    Incomplete(Vec<String>),
+}
+
+impl From<BuilderError> for ConfigError {
+    fn from(error: BuilderError) -> Self {
+        match error {
+            BuilderError::MissingFields(fields) => ConfigError::Incomplete(fields),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Builder)]
+#[builder(error = "ConfigError")]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate, error = "ConfigError")]
+pub struct Server {
+    host: String,
+}
+
+fn main() {
+    let result: Result<Command, ConfigError> = Command::builder().build();
+    assert_eq!(result.err(), Some(ConfigError::Incomplete(vec!["executable".to_owned()])));
+
+    let command = Command::builder().executable("cargo".to_owned()).build().unwrap();
+    assert_eq!(command.executable, "cargo");
+
+    let server: Result<Server, ConfigError> = Server::builder().host("localhost".to_owned()).build();
+    assert_eq!(server.unwrap().host, "localhost");
+}
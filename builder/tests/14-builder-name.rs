@@ -0,0 +1,42 @@
+// The builder is named after the struct it builds, so several derives can
+// live side by side in one module. #[builder(name = "...")] picks a different
+// name when the default one is already taken.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(name = "ServerConfig")]
+pub struct Server {
+    host: String,
+    port: Option<u16>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Client {
+    url: String,
+}
+
+pub struct ServerBuilder;
+
+fn main() {
+    let mut builder: CommandBuilder = Command::builder();
+    let command = builder.executable("cargo".to_owned()).arg("build".to_owned()).build().unwrap();
+    assert_eq!(command.args, vec!["build"]);
+
+    let mut builder: ServerConfig = Server::builder();
+    let server = builder.host("localhost".to_owned()).port(8080).build().unwrap();
+    assert_eq!(server.port, Some(8080));
+
+    let client = Client::builder().url("http://localhost".to_owned()).build().unwrap();
+    assert_eq!(client.url, "http://localhost");
+
+    let _ = ServerBuilder;
+}
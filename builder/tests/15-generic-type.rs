@@ -0,0 +1,52 @@
+// The builder carries the struct's type parameters, including their bounds
+// and defaults, through to the builder struct, builder() and build().
+
+use derive_builder::Builder;
+use std::fmt::Debug;
+
+pub trait Transport: Clone {
+    fn name(&self) -> String;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tcp;
+
+impl Transport for Tcp {
+    fn name(&self) -> String {
+        "tcp".to_owned()
+    }
+}
+
+#[derive(Builder)]
+pub struct Conn<T: Transport, M = String> {
+    transport: T,
+    message: Option<M>,
+    #[builder(each = "retry")]
+    retries: Vec<T>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Pair<A: Debug, B> {
+    left: A,
+    right: B,
+    note: Option<String>,
+}
+
+fn main() {
+    let conn: Conn<Tcp> = Conn::builder()
+        .transport(Tcp)
+        .message("hello".to_owned())
+        .retry(Tcp)
+        .build()
+        .unwrap();
+    assert_eq!(conn.transport.name(), "tcp");
+    assert_eq!(conn.message.as_deref(), Some("hello"));
+    assert_eq!(conn.retries, vec![Tcp]);
+
+    let conn = Conn::<Tcp, u32>::builder().transport(Tcp).build().unwrap();
+    assert!(conn.message.is_none());
+
+    let pair = Pair::builder().right(2u8).left("one").build().unwrap();
+    assert_eq!((pair.left, pair.right), ("one", 2));
+}
@@ -0,0 +1,39 @@
+// Borrowed fields work too: the builder is parameterised over the same
+// lifetimes as the struct.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Request<'a, 'b: 'a> {
+    method: &'a str,
+    path: &'b str,
+    #[builder(each = "header")]
+    headers: Vec<(&'a str, &'b str)>,
+    body: Option<&'a [u8]>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Token<'src> {
+    text: &'src str,
+    line: Option<usize>,
+}
+
+fn main() {
+    let path = String::from("/index.html");
+    let request = Request::builder()
+        .method("GET")
+        .path(&path)
+        .header(("Accept", "text/html"))
+        .build()
+        .unwrap();
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path, "/index.html");
+    assert_eq!(request.headers, vec![("Accept", "text/html")]);
+    assert!(request.body.is_none());
+
+    let source = String::from("fn main() {}");
+    let token = Token::builder().text(&source[..2]).line(1).build().unwrap();
+    assert_eq!(token.text, "fn");
+    assert_eq!(token.line, Some(1));
+}
@@ -0,0 +1,29 @@
+// Const generic parameters are carried over like type parameters.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Frame<const N: usize> {
+    payload: [u8; N],
+    checksum: Option<u32>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Matrix<T, const ROWS: usize, const COLS: usize> {
+    cells: [[T; COLS]; ROWS],
+    label: Option<String>,
+}
+
+fn main() {
+    let frame = Frame::builder().payload([1, 2, 3, 4]).build().unwrap();
+    assert_eq!(frame.payload.len(), 4);
+    assert!(frame.checksum.is_none());
+
+    let matrix: Matrix<i32, 2, 3> = Matrix::builder()
+        .cells([[1, 2, 3], [4, 5, 6]])
+        .label("m".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(matrix.cells[1][2], 6);
+}
@@ -0,0 +1,45 @@
+// Where-clauses on the struct are repeated on the builder and its impls.
+// build() only asks for Clone when it is actually called, so a builder can
+// exist for field types that do not implement it.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Builder)]
+pub struct Index<K, V>
+where
+    K: Hash + Eq,
+    V: Default,
+{
+    entries: HashMap<K, V>,
+    fallback: Option<V>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Cache<K>
+where
+    K: Hash + Eq,
+{
+    capacity: usize,
+    keys: Vec<K>,
+}
+
+#[derive(Default)]
+pub struct NotClone;
+
+fn main() {
+    let mut entries = HashMap::new();
+    entries.insert("a", 1);
+    let index = Index::builder().entries(entries).fallback(0).build().unwrap();
+    assert_eq!(index.entries["a"], 1);
+    assert_eq!(index.fallback, Some(0));
+
+    let cache: Cache<&str> = Cache::builder().keys(vec!["a", "b"]).capacity(16).build().unwrap();
+    assert_eq!(cache.capacity, 16);
+    assert_eq!(cache.keys.len(), 2);
+
+    let mut builder = Index::<u8, NotClone>::builder();
+    builder.fallback(NotClone);
+}
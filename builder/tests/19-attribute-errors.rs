@@ -0,0 +1,56 @@
+// Every malformed #[builder(...)] attribute is reported, each one pointing at
+// the tokens that are wrong. The builder is still generated around them, so
+// code that uses it does not drown the real problems in follow-up errors.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestat)]
+#[builder(constructor = "new_with(program: String)")]
+pub struct Command {
+    executable: String,
+    #[builder(each = arg)]
+    args: Vec<String>,
+    #[builder(each = "not an ident")]
+    env: Vec<String>,
+    #[builder(each = "dir")]
+    current_dir: Option<String>,
+    #[builder]
+    timeout: u64,
+    #[builder(sub_builder)]
+    shell: &'static str,
+    #[builder(merge = "replace")]
+    user: String,
+    #[builder(skip, validate = "check")]
+    cache: Vec<u8>,
+    #[builder(required, default)]
+    priority: Option<u8>,
+    #[builder(optional, env = "RETRIES")]
+    retries: Vec<u8>,
+}
+
+#[derive(Builder)]
+pub struct Target {
+    name: String,
+}
+
+#[derive(Builder)]
+#[builder(constructor = "with_features(features: Vec<String>)")]
+#[builder(constructor = "for_target(target: Target)")]
+pub struct Build {
+    #[builder(each = "feature")]
+    features: Vec<String>,
+    #[builder(sub_builder)]
+    target: Target,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .args(vec!["build".to_owned()])
+        .current_dir("..".to_owned())
+        .timeout(60)
+        .build()
+        .unwrap();
+    let _ = command;
+}
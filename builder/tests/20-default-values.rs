@@ -0,0 +1,81 @@
+// Fields marked #[builder(default)] fall back to Default::default() when they
+// are never set, and #[builder(default = "...")] to an arbitrary expression.
+// A struct-level #[builder(default)] takes every unset field from the
+// struct's own Default impl instead. Defaulted fields are never reported as
+// missing, and default expressions only run inside build() when needed.
+
+use derive_builder::Builder;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static DEFAULT_DIR_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+fn default_dir() -> String {
+    DEFAULT_DIR_CALLS.fetch_add(1, Ordering::SeqCst);
+    "/tmp".to_owned()
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(default)]
+    args: Vec<String>,
+    #[builder(default = "default_dir()")]
+    current_dir: String,
+    #[builder(default = "Some(30)")]
+    timeout: Option<u64>,
+}
+
+#[derive(Builder)]
+#[builder(default)]
+pub struct Server {
+    host: String,
+    port: u16,
+    #[builder(default = "4")]
+    workers: usize,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Server {
+            host: "localhost".to_owned(),
+            port: 80,
+            workers: 1,
+        }
+    }
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Client {
+    url: String,
+    #[builder(default = "3")]
+    retries: u32,
+}
+
+fn main() {
+    let command = Command::builder().executable("cargo".to_owned()).build().unwrap();
+    assert!(command.args.is_empty());
+    assert_eq!(command.current_dir, "/tmp");
+    assert_eq!(command.timeout, Some(30));
+    assert_eq!(DEFAULT_DIR_CALLS.load(Ordering::SeqCst), 1);
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .current_dir("..".to_owned())
+        .timeout(5)
+        .build()
+        .unwrap();
+    assert_eq!(command.current_dir, "..");
+    assert_eq!(command.timeout, Some(5));
+    assert_eq!(DEFAULT_DIR_CALLS.load(Ordering::SeqCst), 1);
+
+    let server = Server::builder().port(8080).build().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.workers, 4);
+
+    let client = Client::builder().url("http://localhost".to_owned()).build().unwrap();
+    assert_eq!(client.retries, 3);
+    let client = Client::builder().retries(0).url("http://localhost".to_owned()).build().unwrap();
+    assert_eq!(client.retries, 0);
+}
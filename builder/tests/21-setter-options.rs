@@ -0,0 +1,87 @@
+// #[builder(setter(...))] changes what a setter accepts.
+//
+//   - `into` takes any `impl Into<T>`, so string literals work for Strings.
+//   - `strip_option = false` makes an Option<T> field's setter take the
+//     whole Option<T>, so a value can be cleared again with None.
+//   - `try_into` adds a `try_<field>` setter taking `impl TryInto<T>` that
+//     hands back the conversion error instead of storing anything.
+//
+// Given on the struct, the options apply to every field that does not say
+// otherwise.
+
+use derive_builder::Builder;
+use std::num::TryFromIntError;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(into))]
+    executable: String,
+    #[builder(each = "arg", setter(into))]
+    args: Vec<String>,
+    #[builder(setter(strip_option = false))]
+    current_dir: Option<String>,
+    #[builder(setter(try_into))]
+    timeout: u16,
+}
+
+#[derive(Builder)]
+#[builder(setter(into, try_into))]
+pub struct Server {
+    host: String,
+    port: u16,
+    #[builder(setter(into = false, try_into = false))]
+    name: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate, setter(into))]
+pub struct Client {
+    url: String,
+    #[builder(setter(try_into))]
+    retries: u8,
+    proxy: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("build")
+        .arg(String::from("--release"))
+        .current_dir(Some("..".to_owned()))
+        .current_dir(None)
+        .try_timeout(30u64)
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.timeout, 30);
+
+    let mut builder = Command::builder();
+    let error: TryFromIntError = builder.try_timeout(100_000u32).err().unwrap();
+    let _ = error;
+
+    let server = Server::builder()
+        .host("localhost")
+        .try_port(8080i64)
+        .unwrap()
+        .name("main".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.name.as_deref(), Some("main"));
+
+    let client = Client::builder()
+        .url("http://localhost")
+        .try_retries(3u32)
+        .unwrap()
+        .proxy("socks5://proxy")
+        .build()
+        .unwrap();
+    assert_eq!(client.url, "http://localhost");
+    assert_eq!(client.retries, 3);
+    assert_eq!(client.proxy.as_deref(), Some("socks5://proxy"));
+    assert!(Client::builder().try_retries(-1i32).is_err());
+}
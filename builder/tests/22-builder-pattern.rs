@@ -0,0 +1,65 @@
+// #[builder(pattern = "...")] decides how setters and build() take the
+// builder.
+//
+//   - "immutable" (the default): setters borrow mutably, build(&self) clones
+//     every field so the builder can be used again.
+//   - "mutable": setters borrow mutably, build(&mut self) takes the values
+//     out of the builder.
+//   - "owned": setters and build(self) consume the builder and move the
+//     values out.
+//
+// Only the immutable pattern needs the field types to implement Clone.
+
+use derive_builder::Builder;
+
+#[derive(Debug, PartialEq)]
+pub struct Handle(u32);
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Job {
+    handle: Handle,
+    #[builder(each = "input")]
+    inputs: Vec<Handle>,
+    output: Option<Handle>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "mutable")]
+pub struct Task {
+    handle: Handle,
+    #[builder(default = "Handle(0)")]
+    parent: Handle,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {
+    let job = Job::builder()
+        .handle(Handle(1))
+        .input(Handle(2))
+        .input(Handle(3))
+        .build()
+        .unwrap();
+    assert_eq!(job.handle, Handle(1));
+    assert_eq!(job.inputs, vec![Handle(2), Handle(3)]);
+    assert_eq!(job.output, None);
+
+    let mut builder = Task::builder();
+    builder.handle(Handle(7));
+    let task = builder.build().unwrap();
+    assert_eq!(task.handle, Handle(7));
+    assert_eq!(task.parent, Handle(0));
+    // The values were taken out, so the builder is empty again
+    assert!(builder.build().is_err());
+
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned());
+    let first = builder.build().unwrap();
+    let second = builder.build().unwrap();
+    assert_eq!(first.executable, second.executable);
+}
@@ -0,0 +1,18 @@
+// A typestate builder changes its type with every required setter, so it is
+// always passed by value and cannot be combined with another pattern.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate, pattern = "mutable")]
+pub struct Command {
+    executable: String,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "borrowed")]
+pub struct Server {
+    host: String,
+}
+
+fn main() {}
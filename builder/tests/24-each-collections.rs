@@ -0,0 +1,63 @@
+// #[builder(each = "...")] works with any collection that implements
+// Default and Extend, not only Vec. Map-like collections get a one-at-a-time
+// setter taking a key and a value. Next to it there is always a
+// `<field>_extend` method that adds a whole iterator of items at once.
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: HashMap<String, String>,
+    #[builder(each = "feature")]
+    features: BTreeSet<&'static str>,
+    #[builder(each = "step", setter(into))]
+    steps: VecDeque<String>,
+    #[builder(each = "limit")]
+    limits: BTreeMap<&'static str, u64>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Server {
+    host: String,
+    #[builder(each = "header")]
+    headers: HashMap<String, String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .args_extend(vec!["--release".to_owned(), "--locked".to_owned()])
+        .env("RUST_LOG".to_owned(), "debug".to_owned())
+        .env_extend([("CARGO_TERM_COLOR".to_owned(), "always".to_owned())])
+        .feature("std")
+        .feature("std")
+        .features_extend(["alloc"])
+        .step("fetch")
+        .step(String::from("compile"))
+        .limit("memory", 1 << 30)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["build", "--release", "--locked"]);
+    assert_eq!(command.env.len(), 2);
+    assert_eq!(command.env["RUST_LOG"], "debug");
+    assert_eq!(command.features.into_iter().collect::<Vec<_>>(), vec!["alloc", "std"]);
+    assert_eq!(command.steps, VecDeque::from(vec!["fetch".to_owned(), "compile".to_owned()]));
+    assert_eq!(command.limits["memory"], 1 << 30);
+
+    let command = Command::builder().build().unwrap();
+    assert!(command.args.is_empty());
+    assert!(command.env.is_empty());
+
+    let server = Server::builder()
+        .header("Accept".to_owned(), "*/*".to_owned())
+        .host("localhost".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(server.headers["Accept"], "*/*");
+}
@@ -0,0 +1,79 @@
+// #[builder(validate = "path")] hooks check invariants before build()
+// constructs anything. A field-level hook receives the value the field was
+// set to, a struct-level hook receives the whole builder so it can check
+// fields against each other. Both return Result<(), String>, and a failure
+// comes back from build() as BuilderError::Validation.
+
+use derive_builder::{Builder, BuilderError};
+
+fn non_empty(value: &String) -> Result<(), String> {
+    if value.is_empty() {
+        Err("must not be empty".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+mod checks {
+    pub fn port_needs_host(builder: &super::ServerBuilder) -> Result<(), String> {
+        match (&builder.host, builder.port) {
+            (Some(_), Some(0)) => Err("port must be non-zero if host is set".to_owned()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Builder)]
+#[builder(validate = "checks::port_needs_host")]
+pub struct Server {
+    #[builder(validate = "non_empty")]
+    name: String,
+    host: Option<String>,
+    port: u16,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Client {
+    #[builder(validate = "non_empty")]
+    url: String,
+}
+
+fn main() {
+    let server = Server::builder()
+        .name("web".to_owned())
+        .host("localhost".to_owned())
+        .port(80)
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 80);
+
+    let server = Server::builder().name("web".to_owned()).port(0).build().unwrap();
+    assert!(server.host.is_none());
+
+    let error = Server::builder()
+        .name("web".to_owned())
+        .host("localhost".to_owned())
+        .port(0)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        error,
+        BuilderError::Validation {
+            field: None,
+            message: "port must be non-zero if host is set".to_owned(),
+        },
+    );
+    assert_eq!(error.to_string(), "validation failed: port must be non-zero if host is set");
+
+    let error = Server::builder().name(String::new()).port(80).build().err().unwrap();
+    assert_eq!(error.to_string(), "invalid `name`: must not be empty");
+
+    // Missing fields are still reported before any hook runs
+    let error = Server::builder().name(String::new()).build().err().unwrap();
+    assert_eq!(error, BuilderError::MissingFields(vec!["port".to_owned()]));
+
+    let error = Client::builder().url(String::new()).build().err().unwrap();
+    assert_eq!(error.to_string(), "invalid `url`: must not be empty");
+}
@@ -0,0 +1,80 @@
+// A finished value can be turned back into its builder, either by cloning it
+// with to_builder() or by moving it with From/Into. Every field of the new
+// builder is already set, so deriving a modified copy only takes the setters
+// for what changes, and the setters of an `each` field append to the
+// elements the value already had.
+//
+// Only to_builder() needs the fields to implement Clone, and only when it is
+// called.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    port: u16,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Handle(u32);
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Job {
+    handle: Handle,
+    retries: u8,
+}
+
+#[derive(Builder)]
+pub struct Request<'a, 'b> {
+    method: &'a str,
+    #[builder(each = "header")]
+    headers: Vec<(&'a str, &'b str)>,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(typestate)]
+pub struct Point<T> {
+    x: T,
+    y: T,
+    label: Option<String>,
+}
+
+fn main() {
+    let cfg = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .port(80)
+        .build()
+        .unwrap();
+
+    let copy = cfg.to_builder().port(8080).arg("--release".to_owned()).build().unwrap();
+    assert_eq!(copy.executable, "cargo");
+    assert_eq!(copy.args, vec!["build", "--release"]);
+    assert_eq!(copy.current_dir, None);
+    assert_eq!(copy.port, 8080);
+
+    // The original is untouched
+    assert_eq!(cfg.port, 80);
+    assert_eq!(cfg.to_builder().build().unwrap(), cfg);
+
+    let job = Job::builder().handle(Handle(1)).retries(3).build().unwrap();
+    let job = JobBuilder::from(job).retries(5).build().unwrap();
+    assert_eq!(job.handle, Handle(1));
+    assert_eq!(job.retries, 5);
+
+    let get = Request::builder().method("GET").header(("Accept", "text/html")).build().unwrap();
+    let head = get.to_builder().method("HEAD").build().unwrap();
+    assert_eq!(head.method, "HEAD");
+    assert_eq!(head.headers, get.headers);
+
+    let point = Point::builder().x(1).y(2).build().unwrap();
+    let moved = point.to_builder().y(5).label("p".to_owned()).build().unwrap();
+    assert_eq!(moved, Point { x: 1, y: 5, label: Some("p".to_owned()) });
+
+    let builder: PointBuilder<i32, i32, i32> = point.into();
+    assert_eq!(builder.build().unwrap(), Point { x: 1, y: 2, label: None });
+}
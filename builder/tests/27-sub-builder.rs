@@ -0,0 +1,129 @@
+// A field whose own type derives Builder can keep that type's builder inside
+// the outer one with #[builder(sub_builder)]. The generated `<field>_mut()`
+// hands out the inner builder, creating it on first use, so nested
+// configuration can be filled in place. The outer build() builds it, and
+// reports anything the inner build() rejects under the field's path, e.g.
+// `server.port`.
+//
+// The plain setter still takes a whole value, which replaces the inner
+// builder. The inner builder's type is `<Type>Builder` unless it is named
+// with #[builder(sub_builder = "...")]. The inner builder's build method
+// may have been renamed with `build_fn`.
+//
+// An outer build() that takes &self builds a clone of the inner builder, so
+// the inner one may use any pattern. So does one that takes &mut self, before
+// it takes anything out, so that an inner builder that fails leaves the outer
+// one as it was.
+
+use derive_builder::{Builder, BuilderError};
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Server {
+    host: String,
+    #[builder(validate = "non_zero")]
+    port: u16,
+}
+
+fn non_zero(port: &u16) -> Result<(), String> {
+    if *port == 0 {
+        Err("must not be zero".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(name = "LimitsConfig", build_fn(name = "finish"))]
+pub struct Limits {
+    #[builder(default = "10")]
+    connections: u32,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "owned")]
+pub struct Tls {
+    #[builder(default)]
+    enabled: bool,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct App {
+    name: String,
+    #[builder(sub_builder)]
+    server: Server,
+    #[builder(sub_builder = "LimitsConfig")]
+    limits: Limits,
+    #[builder(sub_builder)]
+    tls: Tls,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "owned")]
+pub struct Deployment {
+    #[builder(sub_builder)]
+    app: App,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "mutable")]
+pub struct Proxy {
+    name: String,
+    #[builder(sub_builder)]
+    server: Server,
+    #[builder(sub_builder)]
+    tls: Tls,
+}
+
+fn main() {
+    let mut builder = App::builder();
+    builder.name("demo".to_owned());
+    builder.server_mut().host("localhost".to_owned()).port(80);
+    let app = builder.build().unwrap();
+    assert_eq!(app.server, Server { host: "localhost".to_owned(), port: 80 });
+    // An inner builder that was never touched is built from scratch
+    assert_eq!(app.limits, Limits { connections: 10 });
+    assert_eq!(app.tls, Tls { enabled: false });
+
+    builder.limits_mut().connections(5);
+    builder.server_mut().port(8080);
+    let app = builder.build().unwrap();
+    assert_eq!(app.server.port, 8080);
+    assert_eq!(app.limits.connections, 5);
+    assert_eq!(builder.limits_mut().finish().unwrap(), Limits { connections: 5 });
+
+    let mut builder = App::builder();
+    builder.name("demo".to_owned());
+    builder.server_mut().host("localhost".to_owned());
+    let error = builder.build().err().unwrap();
+    assert_eq!(error, BuilderError::MissingFields(vec!["server.port".to_owned()]));
+    assert_eq!(error.to_string(), "missing required field: `server.port`");
+
+    builder.server_mut().port(0);
+    let error = builder.build().err().unwrap();
+    assert_eq!(error.to_string(), "invalid `server.port`: must not be zero");
+
+    builder.server(Server { host: "example.com".to_owned(), port: 443 });
+    assert_eq!(builder.build().unwrap().server.host, "example.com");
+
+    builder.tls(Tls { enabled: true });
+    assert!(builder.build().unwrap().tls.enabled);
+
+    let mut proxy = Proxy::builder();
+    proxy.name("edge".to_owned());
+    proxy.server_mut().host("localhost".to_owned());
+    proxy.tls(Tls { enabled: true });
+    let error = proxy.build().err().unwrap();
+    assert_eq!(error.to_string(), "missing required field: `server.port`");
+    proxy.server_mut().port(80);
+    let built = proxy.build().unwrap();
+    assert_eq!(built.name, "edge");
+    assert_eq!(built.server, Server { host: "localhost".to_owned(), port: 80 });
+    assert!(built.tls.enabled);
+    assert!(proxy.build().is_err());
+
+    // Paths keep growing with every level of nesting
+    let mut deployment = Deployment::builder();
+    deployment.app_mut().name("demo".to_owned()).server_mut().host("localhost".to_owned());
+    let error = deployment.build().err().unwrap();
+    assert_eq!(error.to_string(), "missing required field: `app.server.port`");
+}
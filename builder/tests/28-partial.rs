@@ -0,0 +1,83 @@
+// #[builder(partial)] also generates a `<Struct>Partial`: the same fields,
+// each one public and optional, for configuration that is layered from
+// several sources such as built-in defaults, a config file and the command
+// line. Structs without the attribute get no such type, so the name stays
+// free for the caller's own.
+//
+// `lower.merge(higher)` keeps the fields `higher` sets and fills the rest
+// in from `lower`, and `apply_to` writes the fields that are set onto an
+// existing value. The elements of an `each` field are appended to the lower
+// priority ones, unless the field says #[builder(merge = "replace")].
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(partial)]
+pub struct Config {
+    host: String,
+    port: u16,
+    user: Option<String>,
+    #[builder(each = "include")]
+    includes: Vec<String>,
+    #[builder(each = "feature", merge = "replace")]
+    features: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+}
+
+// Not generated for Server, so the name is free
+pub struct ServerPartial;
+
+fn main() {
+    let file = ConfigPartial {
+        host: Some("example.com".to_owned()),
+        port: Some(80),
+        includes: Some(vec!["base.toml".to_owned()]),
+        features: Some(vec!["tls".to_owned()]),
+        ..Default::default()
+    };
+    let cli = ConfigPartial {
+        port: Some(8080),
+        user: Some(Some("admin".to_owned())),
+        includes: Some(vec!["local.toml".to_owned()]),
+        features: Some(vec!["debug".to_owned()]),
+        ..Default::default()
+    };
+
+    let merged = file.merge(cli);
+    assert_eq!(merged.host.as_deref(), Some("example.com"));
+    assert_eq!(merged.port, Some(8080));
+    assert_eq!(merged.user, Some(Some("admin".to_owned())));
+    assert_eq!(merged.includes, Some(vec!["base.toml".to_owned(), "local.toml".to_owned()]));
+    assert_eq!(merged.features, Some(vec!["debug".to_owned()]));
+
+    let mut config = Config::builder()
+        .host("localhost".to_owned())
+        .port(1)
+        .include("defaults.toml".to_owned())
+        .feature("metrics".to_owned())
+        .build()
+        .unwrap();
+    merged.apply_to(&mut config);
+    assert_eq!(
+        config,
+        Config {
+            host: "example.com".to_owned(),
+            port: 8080,
+            user: Some("admin".to_owned()),
+            includes: vec![
+                "defaults.toml".to_owned(),
+                "base.toml".to_owned(),
+                "local.toml".to_owned(),
+            ],
+            features: vec!["debug".to_owned()],
+        },
+    );
+
+    // Nothing set changes nothing
+    ConfigPartial::default().apply_to(&mut config);
+    assert_eq!(config.port, 8080);
+}
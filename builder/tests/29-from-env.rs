@@ -0,0 +1,118 @@
+// #[builder(env_prefix = "APP_")] generates `from_env()`, which fills the
+// builder from environment variables named after the prefix and the field,
+// e.g. `APP_PORT`. A field can read a differently named variable with
+// #[builder(env = "NAME")]; without a prefix only those fields are read.
+//
+// Values are parsed with FromStr. List fields are split on "," (or what
+// #[builder(env_separator = "...")] says) and every item is parsed on its
+// own. A variable that is not set leaves its field unset, so Option fields
+// just stay None and required fields are reported by build().
+//
+// A #[builder(optional)] field is not read with the prefix, since what it is
+// an Option of can't be seen from its type, and is an error with `env`.
+//
+// `from_env_with` takes the variables from a closure or a map instead of the
+// process environment.
+
+use derive_builder::{Builder, BuilderError, EnvError};
+use std::collections::{BTreeMap, HashMap};
+
+pub type Maybe<T> = Option<T>;
+#[derive(Builder, Debug, PartialEq)]
+#[builder(env_prefix = "APP_")]
+pub struct Server {
+    host: String,
+    port: u16,
+    #[builder(env = "LOG_LEVEL")]
+    log_level: Option<String>,
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+    #[builder(each = "limit", env_separator = ";")]
+    limits: BTreeMap<String, u32>,
+    workers: Vec<u8>,
+    #[builder(optional)]
+    retries: Maybe<u8>,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Paths {
+    #[builder(env = "HOME")]
+    home: String,
+    tmp: Option<String>,
+}
+
+fn main() {
+    let mut vars = HashMap::new();
+    vars.insert("APP_HOST", "localhost");
+    vars.insert("APP_PORT", "8080");
+    vars.insert("APP_TAGS", "web,api");
+    vars.insert("APP_LIMITS", "conns=10;reqs=100");
+    vars.insert("LOG_LEVEL", "debug");
+    vars.insert("APP_RETRIES", "3");
+
+    let server = ServerBuilder::from_env_with(&vars).unwrap().build().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.log_level.as_deref(), Some("debug"));
+    assert_eq!(server.tags, vec!["web", "api"]);
+    assert_eq!(server.limits["conns"], 10);
+    assert_eq!(server.limits["reqs"], 100);
+    assert!(server.workers.is_empty());
+    assert_eq!(server.retries, None);
+
+    // Setters can still override or add to what was read
+    let mut builder = ServerBuilder::from_env_with(&vars).unwrap();
+    builder.port(9090).tag("extra".to_owned());
+    let server = builder.build().unwrap();
+    assert_eq!(server.port, 9090);
+    assert_eq!(server.tags, vec!["web", "api", "extra"]);
+
+    // A closure works as well, and missing variables are left unset
+    let builder = ServerBuilder::from_env_with(|name: &str| match name {
+        "APP_HOST" => Some("example.com".to_owned()),
+        "APP_WORKERS" => Some("1,2".to_owned()),
+        _ => None,
+    })
+    .unwrap();
+    let error = builder.build().err().unwrap();
+    assert_eq!(error, BuilderError::MissingFields(vec!["port".to_owned()]));
+
+    vars.insert("APP_PORT", "http");
+    let error = ServerBuilder::from_env_with(&vars).err().unwrap();
+    assert_eq!(
+        error,
+        EnvError::Parse {
+            variable: "APP_PORT".to_owned(),
+            value: "http".to_owned(),
+            message: "invalid digit found in string".to_owned(),
+        },
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid value \"http\" for environment variable `APP_PORT`: invalid digit found in string",
+    );
+
+    vars.insert("APP_PORT", "80");
+    vars.insert("APP_LIMITS", "conns");
+    let error = ServerBuilder::from_env_with(&vars).err().unwrap();
+    assert_eq!(
+        error.to_string(),
+        "invalid value \"conns\" for environment variable `APP_LIMITS`: expected `key=value`",
+    );
+
+    let paths = PathsBuilder::from_env_with(|name: &str| match name {
+        "HOME" => Some("/home/user".to_owned()),
+        "TMP" => Some("/tmp".to_owned()),
+        _ => None,
+    })
+    .unwrap()
+    .build()
+    .unwrap();
+    assert_eq!(paths, Paths { home: "/home/user".to_owned(), tmp: None });
+
+    // from_env reads the process environment
+    std::env::set_var("APP_HOST", "from-process");
+    std::env::set_var("APP_PORT", "1");
+    let server = ServerBuilder::from_env().unwrap().build().unwrap();
+    assert_eq!(server.host, "from-process");
+}
@@ -0,0 +1,69 @@
+// A builder can be inspected while it is being filled in. Every field gets
+// `<field>_is_set()`, `get_<field>()` returning what it was set to, and
+// `clear_<field>()` to unset it again. The getter of an Option<T> field
+// returns an Option<&T>, like its setter takes a T. `unset_fields()` lists the required
+// fields that build() would still report as missing, e.g. for a UI to
+// prompt for them.
+//
+// Typestate builders track their required fields in the type, so only the
+// other fields get these methods.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default = "30")]
+    timeout: u32,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Job {
+    id: u32,
+    name: String,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Request {
+    url: String,
+    retries: Option<u8>,
+}
+
+fn main() {
+    let _: fn(&CommandBuilder) -> Option<&String> = CommandBuilder::get_current_dir;
+    let _: fn(&CommandBuilder) -> Option<&String> = CommandBuilder::get_executable;
+
+    let mut builder = Command::builder();
+    assert_eq!(builder.unset_fields(), vec!["executable"]);
+    assert!(!builder.current_dir_is_set());
+    assert_eq!(builder.get_current_dir(), None);
+
+    builder.executable("cargo".to_owned()).current_dir("..".to_owned()).arg("build".to_owned());
+    assert!(builder.unset_fields().is_empty());
+    assert!(builder.executable_is_set());
+    assert_eq!(builder.get_executable().map(String::as_str), Some("cargo"));
+    assert_eq!(builder.get_current_dir().map(String::as_str), Some(".."));
+    assert_eq!(builder.get_args(), Some(&vec!["build".to_owned()]));
+    // Fields with a default are never missing, but are only set once set
+    assert!(!builder.timeout_is_set());
+
+    builder.clear_current_dir().clear_executable();
+    assert!(!builder.current_dir_is_set());
+    assert_eq!(builder.unset_fields(), vec!["executable"]);
+    assert!(builder.build().is_err());
+
+    let builder = Job::builder().id(1).name("test".to_owned()).clear_id();
+    assert_eq!(builder.unset_fields(), vec!["id"]);
+    assert_eq!(builder.get_name().map(String::as_str), Some("test"));
+
+    let request = Request::builder().retries(3);
+    assert!(request.retries_is_set());
+    assert_eq!(request.get_retries(), Some(&3));
+    let request = request.clear_retries().url("https://example.com".to_owned()).build().unwrap();
+    assert_eq!(request.retries, None);
+}
@@ -0,0 +1,92 @@
+// Fields that should never be set from outside are left out of the builder.
+// #[builder(skip)] gives the field its Default (or its #[builder(default)]),
+// and #[builder(compute = "path")] calls `path(&value)` once everything else
+// has been built and validated, to work the field out from the rest.
+// Computed fields start out like skipped ones and are computed in
+// declaration order. Both start from the field type's Default, unless they
+// have a #[builder(default = "...")] to start from instead.
+
+use derive_builder::Builder;
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+fn area(rect: &Rect) -> u64 {
+    u64::from(rect.width) * u64::from(rect.height)
+}
+
+fn describe(rect: &Rect) -> String {
+    format!("{}x{} ({})", rect.width, rect.height, rect.area)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Shape {
+    Square,
+    Oblong,
+}
+
+fn shape(rect: &Rect) -> Shape {
+    if rect.width == rect.height {
+        Shape::Square
+    } else {
+        Shape::Oblong
+    }
+}
+
+fn wide(rect: &RectBuilder) -> Result<(), String> {
+    match (rect.get_width(), rect.get_height()) {
+        (Some(width), Some(height)) if width < height => Err("must be wide".to_owned()),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Builder, Debug)]
+#[builder(validate = "wide")]
+pub struct Rect {
+    width: u32,
+    height: u32,
+    #[builder(compute = "area")]
+    area: u64,
+    #[builder(compute = "describe")]
+    label: String,
+    #[builder(compute = "shape", default = "Shape::Square")]
+    shape: Shape,
+    #[builder(skip)]
+    cache: Cell<Option<u64>>,
+    #[builder(skip, default = "7")]
+    version: u8,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Id<T> {
+    value: u64,
+    #[builder(skip)]
+    marker: PhantomData<T>,
+}
+
+#[derive(Builder)]
+pub struct Tagged<T> {
+    name: String,
+    #[builder(skip)]
+    marker: PhantomData<T>,
+}
+
+fn main() {
+    let rect = Rect::builder().width(4).height(3).build().unwrap();
+    assert_eq!(rect.area, 12);
+    assert_eq!(rect.label, "4x3 (12)");
+    assert_eq!(rect.shape, Shape::Oblong);
+    assert_eq!(rect.cache.get(), None);
+    assert_eq!(rect.version, 7);
+
+    // Validation happens before anything is computed
+    assert!(Rect::builder().width(1).height(3).build().is_err());
+
+    let id: Id<String> = Id::builder().value(5).build().unwrap();
+    assert_eq!(id.value, 5);
+    let _ = id.marker;
+
+    let tagged: Tagged<u8> = Tagged::builder().name("x".to_owned()).build().unwrap();
+    assert_eq!(tagged.name, "x");
+    let _ = tagged.marker;
+}
@@ -0,0 +1,34 @@
+// Tuple structs get positional setters, `_0`, `_1` and so on, unless a field
+// is given a name with #[builder(name = "...")]. Everything else works as it
+// does for named fields.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Pair(String, u32);
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Rgb(
+    #[builder(name = "red")] u8,
+    #[builder(name = "green")] u8,
+    #[builder(name = "blue", default)] u8,
+);
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(typestate)]
+pub struct Tagged<T>(#[builder(name = "value")] T, #[builder(each = "tag")] Vec<String>);
+
+fn main() {
+    let pair = Pair::builder()._0("one".to_owned())._1(1).build().unwrap();
+    assert_eq!(pair, Pair("one".to_owned(), 1));
+    assert_eq!(pair.to_builder()._1(2).build().unwrap(), Pair("one".to_owned(), 2));
+
+    let error = Pair::builder()._1(1).build().err().unwrap();
+    assert_eq!(error.to_string(), "missing required field: `_0`");
+
+    let orange = Rgb::builder().red(255).green(165).build().unwrap();
+    assert_eq!(orange, Rgb(255, 165, 0));
+
+    let tagged = Tagged::builder().value(1.5).tag("x".to_owned()).build().unwrap();
+    assert_eq!(tagged, Tagged(1.5, vec!["x".to_owned()]));
+}
@@ -0,0 +1,58 @@
+// Enums get one builder per struct-like variant. `Shape::circle_builder()`
+// starts a `ShapeCircleBuilder`, whose build() returns a `Shape::Circle`.
+// Tuple and unit variants are already easy to write out and get no builder.
+
+use derive_builder::{Builder, BuilderError};
+
+#[derive(Builder, Debug, PartialEq)]
+pub enum Shape {
+    Circle {
+        radius: f64,
+    },
+    Rect {
+        width: f64,
+        height: f64,
+        #[builder(default = "0.0")]
+        rotation: f64,
+    },
+    Point(f64, f64),
+    Empty,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(typestate)]
+pub enum Message<T> {
+    TextMessage { body: T, to: Option<String> },
+}
+
+// Each variant's builder is generic over all of the enum's parameters, also
+// the ones that variant does not use
+#[derive(Builder, Debug, PartialEq)]
+pub enum Event<T> {
+    Tick { count: u32 },
+    Payload { value: T },
+}
+
+fn main() {
+    let circle = Shape::circle_builder().radius(1.0).build().unwrap();
+    assert_eq!(circle, Shape::Circle { radius: 1.0 });
+
+    let mut builder: ShapeRectBuilder = Shape::rect_builder();
+    builder.width(2.0);
+    let error = builder.build().err().unwrap();
+    assert_eq!(error, BuilderError::MissingFields(vec!["height".to_owned()]));
+
+    builder.height(3.0);
+    assert_eq!(builder.build().unwrap(), Shape::Rect { width: 2.0, height: 3.0, rotation: 0.0 });
+
+    let message = Message::text_message_builder().body("hi").build().unwrap();
+    assert_eq!(message, Message::TextMessage { body: "hi", to: None });
+
+    let tick: Event<String> = Event::tick_builder().count(1).build().unwrap();
+    assert_eq!(tick, Event::Tick { count: 1 });
+    let payload = Event::payload_builder().value(7u8).build().unwrap();
+    assert_eq!(payload, Event::Payload { value: 7 });
+
+    let _ = Shape::Point(0.0, 0.0);
+    let _ = Shape::Empty;
+}
@@ -0,0 +1,12 @@
+// Unions have no sensible builder, since only one of their fields can be
+// set, so deriving one is an error.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
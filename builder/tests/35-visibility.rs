@@ -0,0 +1,56 @@
+// The builder and its methods are as visible as the struct, so a private
+// struct no longer leaks a public builder. #[builder(vis = "...")] on the
+// struct changes that for the builder, and on a field for just that field's
+// setters. #[builder(private)] keeps a field's setters to the module that
+// defines the struct.
+
+mod config {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    struct Secret {
+        key: String,
+    }
+
+    #[derive(Builder, Debug)]
+    pub struct Server {
+        pub host: String,
+        #[builder(private)]
+        port: u16,
+        #[builder(vis = "pub(crate)")]
+        workers: Option<u8>,
+    }
+
+    impl ServerBuilder {
+        pub fn default_port(&mut self) -> &mut Self {
+            self.port(80)
+        }
+    }
+
+    #[derive(Builder)]
+    #[builder(vis = "pub(crate)")]
+    pub struct Internal {
+        pub id: u32,
+    }
+
+    pub fn secret() -> String {
+        Secret::builder().key("hunter2".to_owned()).build().unwrap().key
+    }
+}
+
+use config::{Internal, Server};
+
+fn main() {
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .default_port()
+        .workers(4)
+        .build()
+        .unwrap();
+    assert_eq!(server.host, "localhost");
+
+    let internal = Internal::builder().id(1).build().unwrap();
+    assert_eq!(internal.id, 1);
+
+    assert_eq!(config::secret(), "hunter2");
+}
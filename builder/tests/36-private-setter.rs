@@ -0,0 +1,17 @@
+// A #[builder(private)] setter can't be called from outside the module that
+// defines the struct.
+
+mod config {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    pub struct Server {
+        pub host: String,
+        #[builder(private)]
+        pub port: u16,
+    }
+}
+
+fn main() {
+    let _ = config::Server::builder().port(80);
+}
@@ -0,0 +1,66 @@
+//! A field's `///` comments are repeated on its setters and getter, and
+//! builder() and build() list the fields by what happens when they are not
+//! set: required, optional, repeated or defaulted. Everything else that is
+//! generated gets a short doc of its own, so the derive can be used in
+//! crates that deny missing docs.
+
+#![deny(missing_docs)]
+
+use derive_builder::Builder;
+
+/// Where to listen.
+#[derive(Builder)]
+#[builder(env_prefix = "LISTEN_")]
+pub struct Listen {
+    /// Host name or address.
+    pub host: String,
+    /// TCP port, 80 unless set.
+    #[builder(default = "80", setter(try_into))]
+    pub port: u16,
+}
+
+/// The whole configuration.
+#[derive(Builder)]
+pub struct Config {
+    /// Where the server listens.
+    #[builder(sub_builder)]
+    pub listen: Listen,
+    /// Name shown in logs.
+    pub name: Option<String>,
+    /// Extra headers added to every response.
+    #[builder(each = "header")]
+    pub headers: Vec<String>,
+    workers: u8,
+}
+
+/// A request.
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Request {
+    /// Where to send it.
+    pub url: String,
+    /// How often to try again.
+    pub retries: Option<u8>,
+}
+
+/// A shape.
+#[derive(Builder)]
+pub enum Shape {
+    /// A circle.
+    Circle {
+        /// Distance from the center to the edge.
+        radius: f64,
+    },
+}
+
+fn main() {
+    let mut config = Config::builder();
+    config.listen_mut().host("localhost".to_owned());
+    let config = config.workers(4).header("x".to_owned()).build().unwrap();
+    assert_eq!(config.listen.port, 80);
+
+    let request = Request::builder().url("/".to_owned()).build().unwrap();
+    assert_eq!(request.retries, None);
+
+    let _ = Shape::circle_builder().radius(1.0).build().unwrap();
+}
@@ -0,0 +1,80 @@
+// Builders can be derived in #![no_std] crates that have `alloc`, as used in
+// firmware. The generated code only names `::core` items, reaches `String`
+// and `Vec` through derive_builder, and returns an error instead of
+// panicking, so nothing in it needs the standard library.
+//
+// A crate without `extern crate std` cannot resolve `::std` at all, so any
+// leftover `::std` path in the expansion fails to compile here. Only
+// `from_env` still needs std, for reading the process environment, and it is
+// left out when derive_builder's `std` feature is off; tests/no-std checks a
+// library built that way.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use derive_builder::{Builder, BuilderError};
+
+#[derive(Builder, Clone, Debug)]
+pub struct Radio {
+    channel: u8,
+    #[builder(default = "20")]
+    power: u8,
+}
+
+fn positive(gain: &i8) -> Result<(), String> {
+    if *gain > 0 {
+        Ok(())
+    } else {
+        Err(String::from("must be positive"))
+    }
+}
+
+#[derive(Builder, Clone, Debug)]
+pub struct Device {
+    name: String,
+    label: Option<String>,
+    #[builder(each = "sensor")]
+    sensors: Vec<u16>,
+    #[builder(sub_builder)]
+    radio: Radio,
+    #[builder(validate = "positive")]
+    gain: i8,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Packet {
+    id: u32,
+    payload: Vec<u8>,
+}
+
+#[derive(Builder)]
+pub struct Pair(u8, u8);
+
+fn main() {
+    let mut builder = Device::builder();
+    builder.name(String::from("probe")).sensor(1).sensor(2);
+    assert_eq!(builder.unset_fields(), ["gain"]);
+    builder.gain(3);
+    assert_eq!(builder.build().unwrap_err(), BuilderError::MissingFields(alloc::vec![String::from("radio.channel")]));
+
+    builder.radio_mut().channel(11);
+    let device = builder.build().unwrap();
+    assert_eq!(device.name, "probe");
+    assert_eq!(device.label, None);
+    assert_eq!(device.sensors, [1, 2]);
+    assert_eq!(device.radio.power, 20);
+
+    let error = device.to_builder().gain(0).build().unwrap_err();
+    assert!(matches!(error, BuilderError::Validation { .. }));
+
+    let packet = Packet::builder().id(7).build().unwrap();
+    assert_eq!(packet.id, 7);
+    assert!(packet.payload.is_empty());
+
+    let pair = Pair::builder()._0(1)._1(2).build().unwrap();
+    assert_eq!(pair.0 + pair.1, 3);
+}
@@ -0,0 +1,59 @@
+// Option and Vec fields are recognized however their type is spelled, as
+// long as it names the standard type: `Option<T>`, `std::option::Option<T>`
+// and `core::option::Option<T>` all make an optional field, and
+// `alloc::vec::Vec<T>` an empty-by-default one.
+//
+// Type aliases are opaque to the macro, which only sees tokens. A field
+// whose type is an alias of Option or Vec is marked #[builder(optional)] to
+// fall back to its Default. The reverse, #[builder(required)], makes build()
+// insist on an Option or Vec field being set. The setter of a required
+// Option takes the whole Option, so that it can be set to None.
+
+extern crate alloc;
+
+use derive_builder::{Builder, BuilderError};
+
+type Maybe<T> = Option<T>;
+type Args = Vec<String>;
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    cwd: std::option::Option<String>,
+    user: ::core::option::Option<String>,
+    env: alloc::vec::Vec<String>,
+    #[builder(optional)]
+    shell: Maybe<String>,
+    #[builder(optional)]
+    args: Args,
+    #[builder(required)]
+    timeout: Option<u32>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Job {
+    #[builder(required)]
+    retries: std::option::Option<u8>,
+}
+
+fn main() {
+    let error = Command::builder().executable("cargo".to_owned()).build().unwrap_err();
+    assert_eq!(error, BuilderError::MissingFields(vec!["timeout".to_owned()]));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .cwd("..".to_owned())
+        .timeout(None)
+        .build()
+        .unwrap();
+    assert_eq!(command.cwd.as_deref(), Some(".."));
+    assert_eq!(command.user, None);
+    assert!(command.env.is_empty());
+    assert_eq!(command.shell, None);
+    assert!(command.args.is_empty());
+    assert_eq!(command.timeout, None);
+
+    let job = Job::builder().retries(Some(3)).build().unwrap();
+    assert_eq!(job.retries, Some(3));
+}
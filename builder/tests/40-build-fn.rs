@@ -0,0 +1,92 @@
+// The method that finishes a builder can be renamed, and given its error
+// type and struct-level check, with
+// #[builder(build_fn(name = "...", error = "...", validate = "..."))].
+//
+// #[builder(constructor = "new_with(host: String)")] adds an associated fn
+// next to builder() that starts a builder with the listed fields already
+// passed to their setters. For a typestate builder, those fields count as
+// set. The attribute can be repeated for several constructors. Fields that
+// are set one item at a time with `each`, or that have a sub-builder, can't
+// be constructor arguments.
+//
+// #[builder(post_build = "path")] calls `path(&mut value)` on the built value
+// as the last step, to normalise it before it is returned.
+
+use derive_builder::{Builder, BuilderError};
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    Builder(BuilderError),
+}
+
+impl From<BuilderError> for ConfigError {
+    fn from(error: BuilderError) -> Self {
+        ConfigError::Builder(error)
+    }
+}
+
+fn check(builder: &ServerBuilder) -> Result<(), String> {
+    match builder.port {
+        Some(0) => Err("port 0 is not allowed".to_owned()),
+        _ => Ok(()),
+    }
+}
+
+fn normalize(server: &mut Server) {
+    server.host = server.host.to_lowercase();
+}
+
+#[derive(Builder, Debug)]
+#[builder(build_fn(name = "finish", error = "ConfigError", validate = "check"))]
+#[builder(constructor = "new_with(host: String)", constructor = "local(port: u16)")]
+#[builder(post_build = "normalize")]
+pub struct Server {
+    #[builder(setter(into))]
+    host: String,
+    port: u16,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Server::local(8080).host("localhost").finish().unwrap()
+    }
+}
+
+#[derive(Builder)]
+#[builder(typestate, constructor = "to(url: &'static str, retries: u8)")]
+pub struct Request {
+    url: &'static str,
+    retries: Option<u8>,
+    timeout: u64,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned", build_fn(name = "done"), constructor = "named(name: String)")]
+pub struct Job {
+    name: String,
+    priority: u8,
+}
+
+fn main() {
+    let server = Server::new_with("Example.COM".to_owned()).port(443).finish().unwrap();
+    assert_eq!(server.host, "example.com");
+    assert_eq!(server.port, 443);
+
+    let error = Server::local(0).host("localhost").finish().unwrap_err();
+    assert_eq!(error, ConfigError::Builder(BuilderError::Validation {
+        field: None,
+        message: "port 0 is not allowed".to_owned(),
+    }));
+
+    let error = Server::new_with("localhost".to_owned()).finish().unwrap_err();
+    assert_eq!(error, ConfigError::Builder(BuilderError::MissingFields(vec!["port".to_owned()])));
+
+    assert_eq!(Server::default().port, 8080);
+
+    let request = Request::to("/", 3).timeout(30).build().unwrap();
+    assert_eq!(request.url, "/");
+    assert_eq!(request.retries, Some(3));
+
+    let job = Job::named("backup".to_owned()).priority(1).done().unwrap();
+    assert_eq!(job.name, "backup");
+}
@@ -0,0 +1,96 @@
+// Builders implement Clone, so that a half-filled one can be kept as a
+// template, and Debug, which shows every field as either its value or
+// `<unset>`. A field marked #[builder(redact)] is shown as `<redacted>` once
+// it is set, to keep secrets out of logs, and does not need to be Debug.
+//
+// Each impl only exists when every field the builder holds allows it. A
+// field type without Clone or Debug does not stop the builder from being
+// derived; it just leaves the builder without that trait.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Database {
+    host: String,
+    port: Option<u16>,
+    #[builder(redact)]
+    password: String,
+}
+
+#[derive(Builder, Debug)]
+pub struct Service {
+    name: String,
+    #[builder(sub_builder)]
+    database: Database,
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Request<T> {
+    url: String,
+    body: Option<T>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Login {
+    user: String,
+    #[builder(redact)]
+    token: String,
+    marker: (),
+}
+
+pub struct Socket;
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Listener {
+    socket: Socket,
+}
+
+fn main() {
+    let mut template = Database::builder();
+    template.host("localhost".to_owned());
+    assert_eq!(
+        format!("{:?}", template),
+        "DatabaseBuilder { host: \"localhost\", port: <unset>, password: <unset> }",
+    );
+
+    let mut primary = template.clone();
+    primary.password("hunter2".to_owned()).port(5432);
+    assert_eq!(
+        format!("{:?}", primary),
+        "DatabaseBuilder { host: \"localhost\", port: Some(5432), password: <redacted> }",
+    );
+    assert!(template.build().is_err());
+    assert_eq!(primary.build().unwrap().port, Some(5432));
+
+    let mut service = Service::builder();
+    service.name("api".to_owned()).tag("web".to_owned());
+    service.database_mut().host("db".to_owned());
+    assert_eq!(
+        format!("{:?}", service),
+        "ServiceBuilder { name: \"api\", database: DatabaseBuilder { host: \"db\", port: <unset>, password: <unset> }, tags: [\"web\"] }",
+    );
+
+    let request = Request::<u8>::builder();
+    assert_eq!(format!("{:?}", request), "RequestBuilder { url: <unset>, body: <unset> }");
+    let request = request.url("/".to_owned()).body(1);
+    assert_eq!(format!("{:?}", request.clone()), "RequestBuilder { url: \"/\", body: Some(1) }");
+    assert_eq!(request.build().unwrap().body, Some(1));
+
+    // A required field of a typestate builder is unset until its setter has
+    // changed the builder's type, whatever the field's own type is
+    let login = Login::builder();
+    assert_eq!(format!("{:?}", login), "LoginBuilder { user: <unset>, token: <unset>, marker: <unset> }");
+    let login = login.marker(()).token("secret".to_owned());
+    assert_eq!(format!("{:?}", login), "LoginBuilder { user: <unset>, token: <redacted>, marker: () }");
+    let login = login.user("admin".to_owned()).build().unwrap();
+    assert_eq!(login.user, "admin");
+    let _ = (login.token, login.marker);
+
+    let listener = Listener::builder().socket(Socket).build().unwrap();
+    let _ = listener.socket;
+}
@@ -0,0 +1,55 @@
+// #[builder(const)] makes the builder usable in consts and statics: builder()
+// and the plain setters become const fns, and `build_const()` is a const fn
+// that returns the built value itself rather than a Result. The builder
+// uses the owned pattern, and setters take their value as is.
+//
+// A required field that was never set makes build_const() panic, which is a
+// compile error when it happens while evaluating a const or static. Fields
+// that are not set use their #[builder(default = "...")], which has to be
+// an expression that can be evaluated at compile time, or are None or empty
+// for Option and Vec fields. The fallible build() is still there for
+// builders filled in at run time.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(const)]
+pub struct Route {
+    path: &'static str,
+    port: u16,
+    #[builder(default = "4")]
+    workers: u8,
+    timeout: Option<u32>,
+    aliases: Vec<&'static str>,
+    #[builder(skip, default = "0")]
+    hits: u64,
+}
+
+static ROOT: Route = Route::builder().path("/").port(80).build_const();
+
+const ROUTES: [Route; 2] = [
+    Route::builder().path("/api").port(8080).timeout(30).build_const(),
+    Route::builder().path("/static").port(8081).workers(1).build_const(),
+];
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Limit(u32, Option<u32>);
+
+const LIMIT: Limit = Limit::builder()._0(10).build_const();
+
+fn main() {
+    assert_eq!(ROOT.path, "/");
+    assert_eq!(ROOT.workers, 4);
+    assert_eq!(ROOT.timeout, None);
+    assert!(ROOT.aliases.is_empty());
+    assert_eq!(ROOT.hits, 0);
+
+    assert_eq!(ROUTES[0].timeout, Some(30));
+    assert_eq!(ROUTES[1].workers, 1);
+    assert_eq!(LIMIT.0, 10);
+    assert_eq!(LIMIT.1, None);
+
+    let route = Route::builder().path("/").port(1).port(2).build().unwrap();
+    assert_eq!(route, Route::builder().path("/").port(2).build_const());
+}
@@ -0,0 +1,18 @@
+// With #[builder(const)], forgetting a required field of a builder that is
+// evaluated at compile time is a compile error, pointing at the const or
+// static and naming the missing field.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Route {
+    path: &'static str,
+    port: u16,
+}
+
+static ROOT: Route = Route::builder().path("/").build_const();
+
+fn main() {
+    let _ = ROOT.port;
+}
@@ -0,0 +1,97 @@
+// Every field also gets `with_<field>(|value| ...)`, which changes what the
+// builder holds for the field in place. An unset field starts out as its
+// type's Default, and a #[builder(sub_builder)] field as a fresh builder, so
+// a value that was already set, or added to one at a time, can be edited
+// without being taken out and set again.
+//
+// The method is left out for field types without a Default, which can
+// still be set as usual.
+
+use derive_builder::Builder;
+use std::collections::BTreeMap;
+
+#[derive(Builder, Debug)]
+pub struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Builder, Debug)]
+pub struct Service {
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: BTreeMap<String, String>,
+    #[builder(sub_builder)]
+    database: Database,
+    timeout: Option<u32>,
+    retries: u8,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Job {
+    name: String,
+    steps: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Request {
+    url: String,
+    headers: Vec<String>,
+}
+
+pub struct Socket;
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Listener {
+    socket: Socket,
+}
+
+fn main() {
+    let mut builder = Service::builder();
+    builder
+        .arg("run".to_owned())
+        .arg("--release".to_owned())
+        .with_args(|args| args.retain(|arg| arg != "--release"))
+        .with_env(|env| {
+            env.insert("RUST_LOG".to_owned(), "info".to_owned());
+        })
+        .with_database(|database| {
+            database.host("localhost".to_owned()).port(5432);
+        })
+        .with_timeout(|timeout| *timeout = Some(30))
+        .with_retries(|retries| *retries += 1)
+        .with_retries(|retries| *retries += 1)
+        .with_database(|database| {
+            database.port(5433);
+        });
+    let service = builder.build().unwrap();
+    assert_eq!(service.args, ["run"]);
+    assert_eq!(service.env["RUST_LOG"], "info");
+    assert_eq!(service.database.host, "localhost");
+    assert_eq!(service.database.port, 5433);
+    assert_eq!(service.timeout, Some(30));
+    assert_eq!(service.retries, 2);
+
+    let job = Job::builder()
+        .with_name(|name| name.push_str("deploy"))
+        .steps(vec!["build".to_owned()])
+        .with_steps(|steps| steps.push("upload".to_owned()))
+        .build()
+        .unwrap();
+    assert_eq!(job.name, "deploy");
+    assert_eq!(job.steps, ["build", "upload"]);
+
+    let request = Request::builder()
+        .with_headers(|headers| headers.push("Accept: */*".to_owned()))
+        .url("/".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(request.headers.len(), 1);
+
+    let listener = Listener::builder().socket(Socket).build().unwrap();
+    let _ = listener.socket;
+}
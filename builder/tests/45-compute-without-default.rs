@@ -0,0 +1,28 @@
+// A computed field holds its type's Default until it is computed, unless it
+// says what to start from with #[builder(default = "...")]. A type without
+// Default is reported at the field.
+
+use derive_builder::Builder;
+
+pub enum Shape {
+    Square,
+    Oblong,
+}
+
+fn shape(rect: &Rect) -> Shape {
+    if rect.width == rect.height {
+        Shape::Square
+    } else {
+        Shape::Oblong
+    }
+}
+
+#[derive(Builder)]
+pub struct Rect {
+    width: u32,
+    height: u32,
+    #[builder(compute = "shape")]
+    shape: Shape,
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+// A type's Default can't be called in a const fn, so with #[builder(const)]
+// every field the builder doesn't set needs a default that is a const
+// expression: a skipped field needs its #[builder(default = "...")], and a
+// bare #[builder(default)] or #[builder(optional)] is an error, as is
+// #[builder(compute = "...")]. Each error points at the option.
+
+use derive_builder::Builder;
+
+fn checksum(route: &Route) -> u32 {
+    route.port.into()
+}
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Route {
+    port: u16,
+    #[builder(skip)]
+    hits: u64,
+    #[builder(default)]
+    workers: u8,
+    #[builder(optional)]
+    aliases: Vec<&'static str>,
+    #[builder(compute = "checksum")]
+    checksum: u32,
+}
+
+static ROOT: Route = Route::builder().port(80).hits(0).workers(1).aliases(Vec::new()).checksum(0).build_const();
+
+fn main() {
+    let _ = (ROOT.hits, ROOT.checksum);
+}
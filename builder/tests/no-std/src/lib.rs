@@ -0,0 +1,59 @@
+// Without the `std` feature, builders that read their fields from the
+// environment only get `from_env_with`, since there is no process
+// environment to read from.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use derive_builder::{Builder, BuilderError, EnvError};
+
+#[derive(Builder, Clone, Debug)]
+pub struct Radio {
+    channel: u8,
+    #[builder(default = "20")]
+    power: u8,
+}
+
+#[derive(Builder, Clone, Debug)]
+pub struct Device {
+    name: String,
+    label: Option<String>,
+    #[builder(each = "sensor")]
+    sensors: Vec<u16>,
+    #[builder(sub_builder)]
+    radio: Radio,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Packet {
+    id: u32,
+    payload: Vec<u8>,
+}
+
+#[derive(Builder, Debug)]
+#[builder(env_prefix = "FIRMWARE_")]
+pub struct Settings {
+    #[builder(default = "9600")]
+    baud: u32,
+    #[builder(env = "FIRMWARE_NAME")]
+    name: String,
+}
+
+pub fn device() -> Result<Device, BuilderError> {
+    let mut builder = Device::builder();
+    builder.name(String::from("probe")).sensor(1);
+    builder.radio_mut().channel(11);
+    builder.build()
+}
+
+pub fn packet() -> Packet {
+    Packet::builder().id(7).build().unwrap()
+}
+
+pub fn settings(lookup: impl Fn(&str) -> Option<String>) -> Result<SettingsBuilder, EnvError> {
+    SettingsBuilder::from_env_with(lookup)
+}
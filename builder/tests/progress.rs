@@ -8,6 +8,56 @@ fn tests() {
     t.pass("tests/05-method-chaining.rs");
     t.pass("tests/06-optional-field.rs");
     t.pass("tests/07-repeated-field.rs");
-    //t.compile_fail("tests/08-unrecognized-attribute.rs");
+    t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-typestate.rs");
+    t.compile_fail("tests/11-typestate-missing-field.rs");
+    t.pass("tests/12-missing-fields-error.rs");
+    t.pass("tests/13-custom-error.rs");
+    t.pass("tests/14-builder-name.rs");
+    t.pass("tests/15-generic-type.rs");
+    t.pass("tests/16-lifetime.rs");
+    t.pass("tests/17-const-generic.rs");
+    t.pass("tests/18-where-clause.rs");
+    t.compile_fail("tests/19-attribute-errors.rs");
+    t.pass("tests/20-default-values.rs");
+    t.pass("tests/21-setter-options.rs");
+    t.pass("tests/22-builder-pattern.rs");
+    t.compile_fail("tests/23-pattern-typestate.rs");
+    t.pass("tests/24-each-collections.rs");
+    t.pass("tests/25-validation.rs");
+    t.pass("tests/26-to-builder.rs");
+    t.pass("tests/27-sub-builder.rs");
+    t.pass("tests/28-partial.rs");
+    t.pass("tests/29-from-env.rs");
+    t.pass("tests/30-introspection.rs");
+    t.pass("tests/31-skip-compute.rs");
+    t.pass("tests/32-tuple-struct.rs");
+    t.pass("tests/33-enum.rs");
+    t.compile_fail("tests/34-union.rs");
+    t.pass("tests/35-visibility.rs");
+    t.compile_fail("tests/36-private-setter.rs");
+    t.pass("tests/37-docs.rs");
+    t.pass("tests/38-no-std.rs");
+    t.pass("tests/39-option-paths.rs");
+    t.pass("tests/40-build-fn.rs");
+    t.pass("tests/41-debug-clone.rs");
+    t.pass("tests/42-const-builder.rs");
+    t.compile_fail("tests/43-const-missing-field.rs");
+    t.pass("tests/44-with-field.rs");
+    t.compile_fail("tests/45-compute-without-default.rs");
+    t.compile_fail("tests/46-const-defaults.rs");
+}
+
+// Checks a #![no_std] library that uses derive_builder with
+// `default-features = false`, which a trybuild test can't, since those are
+// built as binaries against the crate's default features.
+#[test]
+fn no_std() {
+    let status = std::process::Command::new(env!("CARGO"))
+        .args(["check", "--quiet", "--manifest-path", "tests/no-std/Cargo.toml", "--target-dir"])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/../target/tests/no-std"))
+        .status()
+        .unwrap();
+    assert!(status.success());
 }